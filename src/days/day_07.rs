@@ -1,6 +1,6 @@
-use lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
+use std::io::{Error as IoError, Write};
 
 lazy_static::lazy_static! {
     static ref PARSE_RULE_REGEX: Regex = Regex::new(r"(?P<amount>\d+) (?P<color>.*)").unwrap();
@@ -18,77 +18,227 @@ impl RuleMap {
         }
     }
 
-    fn count_required_bags(&self, color: &str) -> u32 {
-        self.rules
-            .get(color)
-            .unwrap()
-            .iter()
-            .fold(0, |total, (k, count)| {
-                total + count + count * self.count_required_bags(k)
-            })
-    }
+    fn parse_rule(&mut self, raw_rule: &str) -> Result<(), String> {
+        let (container, contents) = raw_rule
+            .split_once(" bags contain ")
+            .ok_or_else(|| format!("Invalid rule: {}", raw_rule))?;
 
-    fn must_contain_bag(&self, key: &str, color: &str) -> bool {
-        self.rules
-            .get(key)
-            .unwrap()
-            .keys()
-            .any(|k| k == color || self.must_contain_bag(k, color))
-    }
-
-    fn find_containers(&self, color: &str) -> HashSet<String> {
-        self.rules
-            .keys()
-            .filter(|k| self.must_contain_bag(k, color))
-            .map(|k| k.to_owned())
-            .collect()
-    }
+        let container = container.to_string();
 
-    fn parse_rule(&mut self, raw_rule: &str) {
-        let parts: Vec<&str> = raw_rule.split(" bags contain ").collect();
-
-        let container = parts[0].to_string();
-
-        if parts[1].contains("no other bags") {
+        if contents.contains("no other bags") {
             self.rules.insert(container, HashMap::new());
         } else {
             let mut map: HashMap<String, u32> = HashMap::new();
 
-            for raw_contained in SPLIT_RULE_REGEX.split(parts[1]) {
+            for raw_contained in SPLIT_RULE_REGEX.split(contents) {
                 if let Some(caps) = PARSE_RULE_REGEX.captures(raw_contained) {
                     map.insert(
-                        (&caps["color"]).to_string(),
-                        (&caps["amount"]).parse().unwrap_or_default(),
+                        caps["color"].to_string(),
+                        caps["amount"].parse().unwrap_or_default(),
                     );
                 }
             }
 
-            if map.len() > 0 {
-                self.rules.insert(container, map);
+            if map.is_empty() {
+                return Err(format!("Rule lists no bags: {}", raw_rule));
             }
+            self.rules.insert(container, map);
         }
+
+        Ok(())
+    }
+}
+
+/// Where a bag is in the depth-first search for cycles.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Unvisited,
+    InProgress,
+    Done,
+}
+
+/// The rules as a graph, built once, with edges both ways between each bag and the bags it must
+/// contain.
+struct BagGraph {
+    colors: Vec<String>,
+    index: HashMap<String, usize>,
+    contents: Vec<Vec<(usize, u64)>>,
+    containers: Vec<Vec<usize>>,
+}
+
+impl BagGraph {
+    /// Parses the rules, one per line, skipping blank lines.
+    fn parse(data: &[&str]) -> Result<Self, String> {
+        let mut rule_map = RuleMap::new();
+        for (i, line) in data.iter().enumerate() {
+            let line = line.trim();
+            if !line.is_empty() {
+                rule_map
+                    .parse_rule(line)
+                    .map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            }
+        }
+        Self::new(&rule_map)
+    }
+
+    /// Builds the graph, failing if a bag refers to a color with no rule.
+    fn new(rule_map: &RuleMap) -> Result<Self, String> {
+        let mut colors: Vec<String> = rule_map.rules.keys().cloned().collect();
+        colors.sort();
+        let index: HashMap<String, usize> = colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| (color.clone(), i))
+            .collect();
+
+        let mut contents = vec![vec![]; colors.len()];
+        let mut containers = vec![vec![]; colors.len()];
+        for (bag, color) in colors.iter().enumerate() {
+            let mut inside: Vec<(&String, &u32)> = rule_map.rules[color].iter().collect();
+            inside.sort();
+            for (other, count) in inside {
+                let other = *index.get(other).ok_or_else(|| {
+                    format!(
+                        "There is no rule for {} bags, which {} bags contain",
+                        other, color
+                    )
+                })?;
+                contents[bag].push((other, *count as u64));
+                containers[other].push(bag);
+            }
+        }
+
+        Ok(Self {
+            colors,
+            index,
+            contents,
+            containers,
+        })
+    }
+
+    /// Counts the bags inside the bag and everything it must contain, visiting each bag's
+    /// contents before the bag itself. Only these bags are checked for cycles, so a cycle
+    /// elsewhere in the rules doesn't stop the count. Returns `None` for the bags that hold too
+    /// many bags to count.
+    fn count_from(&self, bag: usize) -> Result<Vec<Option<u64>>, String> {
+        let mut state = vec![Visit::Unvisited; self.colors.len()];
+        let mut order = vec![];
+        if let Err(cycle) = self.visit(bag, &mut state, &mut vec![], &mut order) {
+            let cycle: Vec<&str> = cycle.iter().map(|b| &self.colors[*b][..]).collect();
+            return Err(format!("Bags contain themselves: {}", cycle.join(" -> ")));
+        }
+
+        let mut required: Vec<Option<u64>> = vec![None; self.colors.len()];
+        for bag in order {
+            required[bag] = self.contents[bag]
+                .iter()
+                .try_fold(0u64, |total, (other, count)| {
+                    let each = required[*other]?.checked_add(1)?;
+                    total.checked_add(count.checked_mul(each)?)
+                });
+        }
+
+        Ok(required)
+    }
+
+    /// Adds the bag to the order after everything inside it, or returns the bags that go round
+    /// in a cycle, starting and ending with the same one.
+    fn visit(
+        &self,
+        bag: usize,
+        state: &mut [Visit],
+        path: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), Vec<usize>> {
+        match state[bag] {
+            Visit::Done => return Ok(()),
+            Visit::InProgress => {
+                let start = path.iter().position(|b| *b == bag).unwrap();
+                let mut cycle = path[start..].to_vec();
+                cycle.push(bag);
+                return Err(cycle);
+            }
+            Visit::Unvisited => {}
+        }
+
+        state[bag] = Visit::InProgress;
+        path.push(bag);
+        for (other, _) in &self.contents[bag] {
+            self.visit(*other, state, path, order)?;
+        }
+        path.pop();
+        state[bag] = Visit::Done;
+        order.push(bag);
+
+        Ok(())
+    }
+
+    fn find(&self, color: &str) -> Result<usize, String> {
+        self.index
+            .get(color)
+            .copied()
+            .ok_or_else(|| format!("There is no rule for {} bags", color))
+    }
+
+    /// Finds the colors of every bag that must contain the given bag, however deep down.
+    fn find_containers(&self, color: &str) -> Result<HashSet<String>, String> {
+        let mut found = HashSet::new();
+        let mut stack = vec![self.find(color)?];
+        while let Some(bag) = stack.pop() {
+            for container in &self.containers[bag] {
+                if found.insert(*container) {
+                    stack.push(*container);
+                }
+            }
+        }
+
+        Ok(found.iter().map(|b| self.colors[*b].clone()).collect())
+    }
+
+    fn count_required_bags(&self, color: &str) -> Result<u64, String> {
+        let bag = self.find(color)?;
+        self.count_from(bag)?[bag]
+            .ok_or_else(|| format!("A {} bag holds too many bags to count", color))
     }
 }
 
 pub fn part_one(data: &[&str]) {
-    let mut rule_map = RuleMap::new();
-    for line in data {
-        rule_map.parse_rule(line);
-    }
-    let count = rule_map.find_containers("shiny gold").len();
-    println!(
-        "Bags that can contain at least one shiny gold bag: {}",
-        count
-    );
+    match BagGraph::parse(data).and_then(|graph| graph.find_containers("shiny gold")) {
+        Ok(containers) => println!(
+            "Bags that can contain at least one shiny gold bag: {}",
+            containers.len()
+        ),
+        Err(e) => println!("{}", e),
+    }
 }
 
 pub fn part_two(data: &[&str]) {
-    let mut rule_map = RuleMap::new();
-    for line in data {
-        rule_map.parse_rule(line);
+    match BagGraph::parse(data).and_then(|graph| graph.count_required_bags("shiny gold")) {
+        Ok(count) => println!("Bags that shiny gold bag requires: {}", count),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Writes how many bags can hold a bag of the given color and how many bags it must hold.
+pub fn describe_bag(data: &[&str], color: &str, out: &mut dyn Write) -> Result<(), IoError> {
+    let graph = match BagGraph::parse(data) {
+        Ok(graph) => graph,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+
+    match graph.find_containers(color) {
+        Ok(containers) => writeln!(
+            out,
+            "Bags that can contain at least one {} bag: {}",
+            color,
+            containers.len()
+        )?,
+        Err(e) => return writeln!(out, "{}", e),
+    }
+    match graph.count_required_bags(color) {
+        Ok(count) => writeln!(out, "Bags that {} bag requires: {}", color, count),
+        Err(e) => writeln!(out, "{}", e),
     }
-    let count = rule_map.count_required_bags("shiny gold");
-    println!("Bags that shiny gold bag requires: {}", count);
 }
 
 #[cfg(test)]
@@ -100,7 +250,7 @@ mod test {
         let raw_rule = "dotted black bags contain no other bags.";
         let mut rule_map = RuleMap::new();
 
-        rule_map.parse_rule(raw_rule);
+        rule_map.parse_rule(raw_rule).unwrap();
 
         assert!(rule_map.rules.contains_key("dotted black"));
         assert_eq!(rule_map.rules.get("dotted black").unwrap().len(), 0);
@@ -111,7 +261,7 @@ mod test {
         let raw_rule = "bright white bags contain 1 shiny gold bag.";
         let mut rule_map = RuleMap::new();
 
-        rule_map.parse_rule(raw_rule);
+        rule_map.parse_rule(raw_rule).unwrap();
 
         let mut result_map: HashMap<String, u32> = HashMap::new();
         result_map.insert("shiny gold".to_string(), 1);
@@ -126,7 +276,7 @@ mod test {
         let raw_rule = "dark orange bags contain 3 bright white bags, 4 muted yellow bags.";
         let mut rule_map = RuleMap::new();
 
-        rule_map.parse_rule(raw_rule);
+        rule_map.parse_rule(raw_rule).unwrap();
         let mut result_map: HashMap<String, u32> = HashMap::new();
         result_map.insert("bright white".to_string(), 3);
         result_map.insert("muted yellow".to_string(), 4);
@@ -136,7 +286,36 @@ mod test {
         assert_eq!(*rule_map.rules.get("dark orange").unwrap(), result_map);
     }
 
-    fn generate_test_rule_map_01() -> RuleMap {
+    #[test]
+    fn day_07_reports_malformed_rules_by_line() {
+        let raw_rules = vec![
+            "faded blue bags contain no other bags.",
+            "",
+            "bright white bags contain 1 faded blue bag.",
+            "shiny gold bags hold 2 faded blue bags.",
+        ];
+
+        assert_eq!(
+            BagGraph::parse(&raw_rules[..3])
+                .unwrap()
+                .count_required_bags("bright white"),
+            Ok(1)
+        );
+        assert_eq!(
+            BagGraph::parse(&raw_rules).err(),
+            Some(String::from(
+                "Line 4: Invalid rule: shiny gold bags hold 2 faded blue bags."
+            ))
+        );
+        assert_eq!(
+            RuleMap::new().parse_rule("shiny gold bags contain some bags."),
+            Err(String::from(
+                "Rule lists no bags: shiny gold bags contain some bags."
+            ))
+        );
+    }
+
+    fn generate_test_graph_01() -> BagGraph {
         let raw_rules = vec![
             "light red bags contain 1 bright white bag, 2 muted yellow bags.",
             "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
@@ -149,18 +328,12 @@ mod test {
             "dotted black bags contain no other bags.",
         ];
 
-        let mut rule_map = RuleMap::new();
-
-        for raw_rule in raw_rules {
-            rule_map.parse_rule(raw_rule);
-        }
-
-        rule_map
+        BagGraph::parse(&raw_rules).unwrap()
     }
 
     #[test]
     fn day_07_identifies_containers() {
-        let rule_map = generate_test_rule_map_01();
+        let graph = generate_test_graph_01();
 
         let mut expected: HashSet<String> = HashSet::new();
         expected.insert("bright white".to_string());
@@ -168,10 +341,10 @@ mod test {
         expected.insert("dark orange".to_string());
         expected.insert("light red".to_string());
 
-        assert_eq!(rule_map.find_containers("shiny gold"), expected);
+        assert_eq!(graph.find_containers("shiny gold"), Ok(expected));
     }
 
-    fn generate_test_rule_map_02() -> RuleMap {
+    fn generate_test_graph_02() -> BagGraph {
         let raw_rules = vec![
             "shiny gold bags contain 2 dark red bags.",
             "dark red bags contain 2 dark orange bags.",
@@ -182,19 +355,85 @@ mod test {
             "dark violet bags contain no other bags.",
         ];
 
-        let mut rule_map = RuleMap::new();
+        BagGraph::parse(&raw_rules).unwrap()
+    }
 
-        for raw_rule in raw_rules {
-            rule_map.parse_rule(raw_rule);
-        }
+    #[test]
+    fn day_07_counts_bags_required_for_specified_bag() {
+        let graph = generate_test_graph_02();
 
-        rule_map
+        assert_eq!(graph.count_required_bags("shiny gold"), Ok(126));
     }
 
     #[test]
-    fn day_07_counts_bags_required_for_specified_bag() {
-        let rule_map = generate_test_rule_map_02();
+    fn day_07_answers_for_any_color() {
+        let graph = generate_test_graph_01();
+
+        assert_eq!(graph.count_required_bags("shiny gold"), Ok(32));
+        assert_eq!(graph.count_required_bags("dark olive"), Ok(7));
+        assert_eq!(graph.count_required_bags("faded blue"), Ok(0));
+        assert_eq!(graph.find_containers("light red"), Ok(HashSet::new()));
+        assert_eq!(graph.find_containers("dark olive").unwrap().len(), 5);
+        assert_eq!(
+            graph.count_required_bags("plain grey"),
+            Err(String::from("There is no rule for plain grey bags"))
+        );
+    }
+
+    #[test]
+    fn day_07_counts_deep_rule_sets() {
+        // Every level holds two bags of each colour on the next, so there are 2^n paths down.
+        let mut raw_rules = vec![];
+        for level in 0..40 {
+            raw_rules.push(format!(
+                "light c{0} bags contain 2 light c{1} bags, 2 dark c{1} bags.",
+                level,
+                level + 1
+            ));
+            raw_rules.push(format!(
+                "dark c{0} bags contain 1 light c{1} bag, 1 dark c{1} bag.",
+                level,
+                level + 1
+            ));
+        }
+        raw_rules.push(String::from("light c40 bags contain no other bags."));
+        raw_rules.push(String::from("dark c40 bags contain no other bags."));
+        let raw_rules: Vec<&str> = raw_rules.iter().map(String::as_str).collect();
+
+        let graph = BagGraph::parse(&raw_rules).unwrap();
 
-        assert_eq!(rule_map.count_required_bags("shiny gold"), 126);
+        assert_eq!(graph.find_containers("light c40").unwrap().len(), 80);
+        assert_eq!(graph.count_required_bags("dark c39"), Ok(2));
+        assert_eq!(graph.count_required_bags("dark c38"), Ok(2 + 2 * 2 + 2));
+        assert!(graph.count_required_bags("light c0").is_err());
+    }
+
+    #[test]
+    fn day_07_rejects_cycles_and_missing_rules() {
+        let cyclic = vec![
+            "light red bags contain 1 bright white bag.",
+            "bright white bags contain 2 muted yellow bags.",
+            "muted yellow bags contain 1 light red bag.",
+            "shiny gold bags contain 1 light red bag, 2 dotted black bags.",
+            "dotted black bags contain no other bags.",
+        ];
+        let missing = vec!["light red bags contain 1 bright white bag."];
+
+        let graph = BagGraph::parse(&cyclic).unwrap();
+
+        assert_eq!(
+            graph.count_required_bags("shiny gold"),
+            Err(String::from(
+                "Bags contain themselves: light red -> bright white -> muted yellow -> light red"
+            ))
+        );
+        assert_eq!(graph.count_required_bags("dotted black"), Ok(0));
+        assert_eq!(graph.find_containers("light red").unwrap().len(), 4);
+        assert_eq!(
+            BagGraph::parse(&missing).err(),
+            Some(String::from(
+                "There is no rule for bright white bags, which light red bags contain"
+            ))
+        );
     }
 }
@@ -0,0 +1,419 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::processor::{Instruction, REGISTER_COUNT};
+
+/// Where control goes after the last instruction of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Continues with the block at the given index.
+    Block(usize),
+    /// Moves just past the last instruction, halting the program.
+    End,
+    /// Jumps to the given address outside the program.
+    OutOfBounds(i64),
+}
+
+/// A run of instructions that always execute together, from `start` up to but not including
+/// `end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub end: usize,
+    pub exit: Exit,
+}
+
+/// A loop in the control-flow graph, along with how much one trip around it changes each
+/// register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// The blocks in the order they run, starting with the lowest index.
+    pub blocks: Vec<usize>,
+    pub deltas: [i32; REGISTER_COUNT],
+}
+
+impl Display for Cycle {
+    /// Describes the registers after `n` trips around the cycle in terms of their values on
+    /// entry, such as `acc + 5n`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let terms: Vec<String> = self
+            .deltas
+            .iter()
+            .enumerate()
+            .filter(|(_, delta)| **delta != 0)
+            .map(|(register, delta)| {
+                let name = match register {
+                    0 => String::from("acc"),
+                    r => format!("r{}", r),
+                };
+                let sign = if *delta < 0 { '-' } else { '+' };
+                match delta.abs() {
+                    1 => format!("{} = {} {} n", name, name, sign),
+                    step => format!("{} = {} {} {}n", name, name, sign, step),
+                }
+            })
+            .collect();
+
+        match terms.is_empty() {
+            true => write!(f, "registers unchanged after n iterations"),
+            false => write!(f, "after n iterations: {}", terms.join(", ")),
+        }
+    }
+}
+
+/// The control-flow graph of a program, split into basic blocks.
+pub struct Analysis<'a> {
+    instructions: &'a [Instruction],
+    pub blocks: Vec<BasicBlock>,
+    /// Whether each block can run when the program starts at address 0.
+    pub reachable: Vec<bool>,
+    pub cycles: Vec<Cycle>,
+}
+
+impl<'a> Analysis<'a> {
+    /// Analyzes a program without running it.
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        let blocks = find_blocks(instructions);
+        let reachable = find_reachable(&blocks);
+        let cycles = find_cycles(&blocks)
+            .into_iter()
+            .map(|blocks_in_cycle| {
+                let mut deltas = [0; REGISTER_COUNT];
+                for &block in &blocks_in_cycle {
+                    let BasicBlock { start, end, .. } = blocks[block];
+                    for instruction in &instructions[start..end] {
+                        for (delta, effect) in deltas.iter_mut().zip(instruction.effect().iter()) {
+                            *delta += effect;
+                        }
+                    }
+                }
+                Cycle {
+                    blocks: blocks_in_cycle,
+                    deltas,
+                }
+            })
+            .collect();
+
+        Self {
+            instructions,
+            blocks,
+            reachable,
+            cycles,
+        }
+    }
+
+    /// Returns the address ranges of every instruction that can never run.
+    pub fn unreachable_code(&self) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = vec![];
+        for (block, reachable) in self.blocks.iter().zip(self.reachable.iter()) {
+            if *reachable {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((_, end)) if *end == block.start => *end = block.end,
+                _ => ranges.push((block.start, block.end)),
+            }
+        }
+        ranges
+    }
+
+    /// Renders the control-flow graph in Graphviz's DOT language. Unreachable blocks are drawn
+    /// dashed, and edges that belong to a cycle are drawn in red.
+    pub fn to_dot(&self) -> String {
+        let cycle_edges: BTreeSet<(usize, usize)> = self
+            .cycles
+            .iter()
+            .flat_map(|cycle| {
+                let next = cycle.blocks.iter().cycle().skip(1);
+                cycle.blocks.iter().copied().zip(next.copied())
+            })
+            .collect();
+
+        let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+        for (index, block) in self.blocks.iter().enumerate() {
+            let mut label = format!("b{}: {}-{}\\l", index, block.start, block.end - 1);
+            for address in block.start..block.end {
+                label.push_str(&format!(
+                    "{:>4}: {}\\l",
+                    address, self.instructions[address]
+                ));
+            }
+            let style = match self.reachable[index] {
+                true => "",
+                false => ", style=dashed",
+            };
+            dot.push_str(&format!("    b{} [label=\"{}\"{}];\n", index, label, style));
+        }
+
+        dot.push_str("    end [shape=doublecircle];\n");
+        for (index, block) in self.blocks.iter().enumerate() {
+            match block.exit {
+                Exit::Block(next) => {
+                    let color = match cycle_edges.contains(&(index, next)) {
+                        true => " [color=red]",
+                        false => "",
+                    };
+                    dot.push_str(&format!("    b{} -> b{}{};\n", index, next, color));
+                }
+                Exit::End => dot.push_str(&format!("    b{} -> end;\n", index)),
+                Exit::OutOfBounds(target) => {
+                    dot.push_str(&format!(
+                        "    oob{} [label=\"{}\", shape=octagon];\n    b{} -> oob{};\n",
+                        index, target, index, index
+                    ));
+                }
+            }
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+impl Display for Analysis<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "Basic blocks:")?;
+        for (index, block) in self.blocks.iter().enumerate() {
+            let exit = match block.exit {
+                Exit::Block(next) => format!("b{}", next),
+                Exit::End => String::from("end"),
+                Exit::OutOfBounds(target) => format!("out of bounds ({})", target),
+            };
+            writeln!(
+                f,
+                "  b{}: {}-{} -> {}",
+                index,
+                block.start,
+                block.end - 1,
+                exit
+            )?;
+        }
+
+        writeln!(f, "Unreachable code:")?;
+        for (start, end) in self.unreachable_code() {
+            writeln!(f, "  {}-{}", start, end - 1)?;
+        }
+
+        writeln!(f, "Cycles:")?;
+        for cycle in &self.cycles {
+            let path: Vec<String> = cycle.blocks.iter().map(|b| format!("b{}", b)).collect();
+            let reachable = match self.reachable[cycle.blocks[0]] {
+                true => "",
+                false => " (unreachable)",
+            };
+            writeln!(
+                f,
+                "  {} -> b{}{}: {}",
+                path.join(" -> "),
+                cycle.blocks[0],
+                reachable,
+                cycle
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the program into basic blocks. A block starts at address 0, at the target of any jump
+/// and just after any jump.
+fn find_blocks(instructions: &[Instruction]) -> Vec<BasicBlock> {
+    let len = instructions.len();
+    let mut leaders: BTreeSet<usize> = BTreeSet::new();
+    if len > 0 {
+        leaders.insert(0);
+    }
+    for (address, instruction) in instructions.iter().enumerate() {
+        let target = instruction.successor(address);
+        if target != address as i64 + 1 {
+            if (0..len as i64).contains(&target) {
+                leaders.insert(target as usize);
+            }
+            if address + 1 < len {
+                leaders.insert(address + 1);
+            }
+        }
+    }
+
+    let starts: Vec<usize> = leaders.into_iter().collect();
+    let block_at = |address: i64| -> Exit {
+        match address {
+            a if a == len as i64 => Exit::End,
+            a if a < 0 || a > len as i64 => Exit::OutOfBounds(a),
+            a => Exit::Block(starts.binary_search(&(a as usize)).unwrap()),
+        }
+    };
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(index, &start)| {
+            let end = starts.get(index + 1).copied().unwrap_or(len);
+            BasicBlock {
+                start,
+                end,
+                exit: block_at(instructions[end - 1].successor(end - 1)),
+            }
+        })
+        .collect()
+}
+
+/// Marks the blocks reachable from the first one.
+fn find_reachable(blocks: &[BasicBlock]) -> Vec<bool> {
+    let mut reachable = vec![false; blocks.len()];
+    let mut block = 0;
+    while block < blocks.len() && !reachable[block] {
+        reachable[block] = true;
+        match blocks[block].exit {
+            Exit::Block(next) => block = next,
+            _ => break,
+        }
+    }
+    reachable
+}
+
+/// Finds every cycle of blocks. Each block has at most one successor, so following the exits
+/// from every block in turn finds each cycle exactly once.
+fn find_cycles(blocks: &[BasicBlock]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut walk_of = vec![UNVISITED; blocks.len()];
+    let mut cycles = vec![];
+
+    for first in 0..blocks.len() {
+        let mut path = vec![];
+        let mut block = Some(first);
+        while let Some(current) = block {
+            if walk_of[current] != UNVISITED {
+                break;
+            }
+            walk_of[current] = first;
+            path.push(current);
+            block = match blocks[current].exit {
+                Exit::Block(next) => Some(next),
+                _ => None,
+            };
+        }
+
+        // Running into a block from this same walk closes a new cycle, while running into one
+        // from an earlier walk leads somewhere already explored.
+        if let Some(repeated) = block.filter(|b| walk_of[*b] == first) {
+            let position = path.iter().position(|b| *b == repeated).unwrap();
+            let mut cycle = path.split_off(position);
+            let lowest = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+            cycle.rotate_left(lowest);
+            cycles.push(cycle);
+        }
+    }
+
+    cycles.sort();
+    cycles
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    fn sample() -> Vec<Instruction> {
+        parse(&[
+            "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
+            "acc +6",
+        ])
+    }
+
+    #[test]
+    fn day_08_splits_program_into_basic_blocks() {
+        let instructions = sample();
+        let analysis = Analysis::new(&instructions);
+
+        let ranges: Vec<(usize, usize, Exit)> = analysis
+            .blocks
+            .iter()
+            .map(|b| (b.start, b.end, b.exit))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, 1, Exit::Block(1)),
+                (1, 3, Exit::Block(4)),
+                (3, 5, Exit::Block(1)),
+                (5, 6, Exit::Block(4)),
+                (6, 8, Exit::Block(2)),
+                (8, 9, Exit::End),
+            ],
+            ranges
+        );
+    }
+
+    #[test]
+    fn day_08_finds_unreachable_code() {
+        let instructions = sample();
+        let analysis = Analysis::new(&instructions);
+
+        assert_eq!(vec![(5, 6), (8, 9)], analysis.unreachable_code());
+    }
+
+    #[test]
+    fn day_08_summarizes_cycle_deltas() {
+        let instructions = sample();
+        let analysis = Analysis::new(&instructions);
+
+        assert_eq!(
+            vec![Cycle {
+                blocks: vec![1, 4, 2],
+                deltas: [5, 0, 0, 0]
+            }],
+            analysis.cycles
+        );
+        assert_eq!(
+            "after n iterations: acc = acc + 5n",
+            analysis.cycles[0].to_string()
+        );
+    }
+
+    #[test]
+    fn day_08_finds_every_cycle() {
+        let instructions = parse(&[
+            "jmp +3",
+            "acc r1 -2",
+            "jmp -1",
+            "acc +1",
+            "jmp -1",
+            "nop +0",
+            "jmp -1",
+        ]);
+        let analysis = Analysis::new(&instructions);
+
+        let cycles: Vec<String> = analysis
+            .cycles
+            .iter()
+            .map(|c| format!("{:?} {}", c.blocks, c))
+            .collect();
+        assert_eq!(
+            vec![
+                "[1] after n iterations: r1 = r1 - 2n",
+                "[2] after n iterations: acc = acc + n",
+                "[3] registers unchanged after n iterations",
+            ],
+            cycles
+        );
+        assert_eq!(vec![true, false, true, false], analysis.reachable);
+    }
+
+    #[test]
+    fn day_08_renders_dot_graph() {
+        let instructions = parse(&["acc +1", "jmp -1", "jmp +5"]);
+        let analysis = Analysis::new(&instructions);
+
+        let dot = analysis.to_dot();
+
+        assert!(dot.starts_with("digraph cfg {\n"));
+        assert!(dot.contains("    b0 [label=\"b0: 0-1\\l   0: acc +1\\l   1: jmp -1\\l\"];\n"));
+        assert!(dot.contains("    b0 -> b0 [color=red];\n"));
+        assert!(dot.contains("b1 [label=\"b1: 2-2\\l   2: jmp +5\\l\", style=dashed];\n"));
+        assert!(dot.contains("    b1 -> oob1;\n"));
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::processor::{Instruction, ParseError};
+
+/// Starts a comment that runs to the end of the line.
+const COMMENT: char = '#';
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AssemblyError {
+    InvalidInstruction { line: usize, error: ParseError },
+    InvalidLabel { line: usize, label: String },
+    DuplicateLabel { line: usize, label: String },
+    UndefinedLabel { line: usize, label: String },
+}
+
+impl Display for AssemblyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::InvalidInstruction { line, error } => write!(f, "Line {}: {}", line, error),
+            Self::InvalidLabel { line, label } => {
+                write!(f, "Line {}: Invalid label: {}", line, label)
+            }
+            Self::DuplicateLabel { line, label } => {
+                write!(f, "Line {}: Duplicate label: {}", line, label)
+            }
+            Self::UndefinedLabel { line, label } => {
+                write!(f, "Line {}: Undefined label: {}", line, label)
+            }
+        }
+    }
+}
+
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Assembles source code into a program. Each line holds at most one instruction, and may start
+/// with any number of labels such as `loop:`. Everything after a `#` is a comment. An argument
+/// can name a label instead of giving an offset, in which case the offset is the distance to the
+/// labeled instruction. A label after the last instruction refers to the end of the program.
+pub fn assemble<T: AsRef<str>>(source: &[T]) -> Result<Vec<Instruction>, AssemblyError> {
+    let mut labels: HashMap<&str, usize> = HashMap::new();
+    let mut statements: Vec<(usize, &str)> = vec![];
+
+    for (index, line) in source.iter().enumerate() {
+        let number = index + 1;
+        let mut text = line.as_ref();
+        if let Some(start) = text.find(COMMENT) {
+            text = &text[..start];
+        }
+
+        while let Some((label, rest)) = text.split_once(':') {
+            let label = label.trim();
+            if !is_label(label) {
+                return Err(AssemblyError::InvalidLabel {
+                    line: number,
+                    label: label.to_owned(),
+                });
+            }
+            if labels.insert(label, statements.len()).is_some() {
+                return Err(AssemblyError::DuplicateLabel {
+                    line: number,
+                    label: label.to_owned(),
+                });
+            }
+            text = rest;
+        }
+
+        let text = text.trim();
+        if !text.is_empty() {
+            statements.push((number, text));
+        }
+    }
+
+    statements
+        .iter()
+        .enumerate()
+        .map(|(address, (number, text))| {
+            let mut parts: Vec<String> = text.split_whitespace().map(str::to_owned).collect();
+            if let [_, .., argument] = &mut parts[..] {
+                if is_label(argument) {
+                    let target = labels.get(argument.as_str()).ok_or_else(|| {
+                        AssemblyError::UndefinedLabel {
+                            line: *number,
+                            label: argument.clone(),
+                        }
+                    })?;
+                    *argument = format!("{:+}", *target as i64 - address as i64);
+                }
+            }
+
+            parts
+                .join(" ")
+                .parse()
+                .map_err(|error| AssemblyError::InvalidInstruction {
+                    line: *number,
+                    error,
+                })
+        })
+        .collect()
+}
+
+/// Turns a program back into source code, labeling the target of every jump. Assembling the
+/// result gives back the same program.
+pub fn disassemble(instructions: &[Instruction]) -> Vec<String> {
+    let end = instructions.len() as i64;
+    let is_jump = |address: usize, instruction: &Instruction| {
+        let target = instruction.successor(address);
+        target != address as i64 + 1 && (0..=end).contains(&target)
+    };
+    let targets: BTreeSet<i64> = instructions
+        .iter()
+        .enumerate()
+        .filter(|(address, instruction)| is_jump(*address, instruction))
+        .map(|(address, instruction)| instruction.successor(address))
+        .collect();
+
+    let mut source = vec![];
+    for (address, instruction) in instructions.iter().enumerate() {
+        if targets.contains(&(address as i64)) {
+            source.push(format!("L{}:", address));
+        }
+        let mut line = format!("    {}", instruction);
+        if is_jump(address, instruction) {
+            let offset = format!("{:+}", instruction.argument);
+            line.truncate(line.len() - offset.len());
+            line.push_str(&format!("L{}", instruction.successor(address)));
+        }
+        source.push(line);
+    }
+    if targets.contains(&end) {
+        source.push(format!("L{}:", end));
+    }
+
+    source
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::processor::Opcode;
+    use super::*;
+
+    #[test]
+    fn day_08_assembles_labels_and_comments() {
+        let source = [
+            "# Loops back to the start until the jump is patched",
+            "start:  acc +1   # once per pass",
+            "        nop +0",
+            "        jmp done",
+            "        jmp start",
+            "done:",
+        ];
+
+        assert_eq!(
+            Ok(vec![
+                Instruction::new(Opcode::Acc, 1),
+                Instruction::new(Opcode::Nop, 0),
+                Instruction::new(Opcode::Jmp, 2),
+                Instruction::new(Opcode::Jmp, -3),
+            ]),
+            assemble(&source)
+        );
+    }
+
+    #[test]
+    fn day_08_assembles_plain_puzzle_input() {
+        let source = ["nop +0", "acc +1", "jmp -2"];
+
+        let program = assemble(&source).unwrap();
+
+        assert_eq!(Instruction::new(Opcode::Jmp, -2), program[2]);
+    }
+
+    #[test]
+    fn day_08_reports_assembly_errors_with_line_numbers() {
+        assert_eq!(
+            Err(AssemblyError::UndefinedLabel {
+                line: 2,
+                label: "nowhere".to_string()
+            }),
+            assemble(&["nop +0", "jmp nowhere"])
+        );
+        assert_eq!(
+            Err(AssemblyError::DuplicateLabel {
+                line: 3,
+                label: "a".to_string()
+            }),
+            assemble(&["a: nop +0", "", "a: nop +0"])
+        );
+        assert_eq!(
+            Err(AssemblyError::InvalidLabel {
+                line: 1,
+                label: "1st".to_string()
+            }),
+            assemble(&["1st: nop +0"])
+        );
+        assert_eq!(
+            "Line 1: Unrecognized instruction: mul +2",
+            assemble(&["mul +2"]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn day_08_disassembly_round_trips() {
+        let source = [
+            "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
+            "acc +6", "jmp +1", "jmp +50",
+        ];
+        let program = assemble(&source).unwrap();
+
+        let disassembly = disassemble(&program);
+
+        assert_eq!("L1:", disassembly[1]);
+        assert!(disassembly.contains(&"    jmp L6".to_string()));
+        assert!(disassembly.contains(&"    jmp +50".to_string()));
+        assert_eq!(Ok(program), assemble(&disassembly));
+    }
+}
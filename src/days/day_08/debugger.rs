@@ -0,0 +1,409 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{BufRead, Error as IoError, Write};
+use std::str::FromStr;
+
+use super::processor::{Instruction, Processor, Termination};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Step(usize),
+    Continue,
+    Break(usize),
+    Delete(usize),
+    Watch,
+    Print,
+    Trace,
+    Help,
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum CommandError {
+    Unrecognized(String),
+    MissingAddress(String),
+    InvalidNumber(String),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Unrecognized(command) => write!(f, "Unrecognized command: {}", command),
+            Self::MissingAddress(command) => write!(f, "Missing address: {}", command),
+            Self::InvalidNumber(command) => write!(f, "Invalid number: {}", command),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let number = |index: usize| -> Result<Option<usize>, CommandError> {
+            match parts.get(index) {
+                Some(n) => n
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| CommandError::InvalidNumber(s.to_owned())),
+                None => Ok(None),
+            }
+        };
+        let address = |index: usize| -> Result<usize, CommandError> {
+            number(index)?.ok_or_else(|| CommandError::MissingAddress(s.to_owned()))
+        };
+
+        match parts.first().copied() {
+            Some("step") | Some("s") => Ok(Command::Step(number(1)?.unwrap_or(1))),
+            Some("continue") | Some("c") => Ok(Command::Continue),
+            Some("break") | Some("b") => Ok(Command::Break(address(1)?)),
+            Some("delete") | Some("d") => Ok(Command::Delete(address(1)?)),
+            Some("watch") | Some("w") => Ok(Command::Watch),
+            Some("print") | Some("p") => Ok(Command::Print),
+            Some("trace") | Some("t") => Ok(Command::Trace),
+            Some("help") | Some("h") => Ok(Command::Help),
+            Some("quit") | Some("q") => Ok(Command::Quit),
+            _ => Err(CommandError::Unrecognized(s.to_owned())),
+        }
+    }
+}
+
+const HELP: &str = "\
+step [n]        execute the next n instructions (default 1)
+continue        run until a breakpoint or the program stops
+break <addr>    stop before executing the instruction at an address
+delete <addr>   remove a breakpoint
+watch           toggle reporting every change to the accumulator
+print           show the instruction pointer, registers and breakpoints
+trace           dump every executed instruction and how often each address ran
+quit            end the session";
+
+/// One executed instruction.
+struct TraceEntry {
+    address: usize,
+    instruction: Instruction,
+    accumulator: i32,
+}
+
+/// Runs a program under the control of debugger commands.
+struct Debugger {
+    processor: Processor,
+    breakpoints: BTreeSet<usize>,
+    watching: bool,
+    trace: Vec<TraceEntry>,
+    visits: Vec<usize>,
+    stopped: Option<Termination>,
+    /// Whether `continue` last stopped at the breakpoint the instruction pointer is on, so the
+    /// next `continue` runs past it instead of stopping there again.
+    at_breakpoint: bool,
+}
+
+impl Debugger {
+    fn new(processor: Processor) -> Self {
+        let visits = vec![0; processor.instructions().len()];
+        Self {
+            processor,
+            breakpoints: BTreeSet::new(),
+            watching: false,
+            trace: vec![],
+            visits,
+            stopped: None,
+            at_breakpoint: false,
+        }
+    }
+
+    /// Executes one instruction, returning whether the program can keep going.
+    fn step(&mut self, out: &mut dyn Write, echo: bool) -> Result<bool, IoError> {
+        if let Some(termination) = self.stopped {
+            writeln!(out, "Program already stopped: {}", termination)?;
+            return Ok(false);
+        }
+
+        let address = self.processor.instruction_pointer();
+        let before = self.processor.accumulator();
+
+        let termination = self.processor.step();
+        self.at_breakpoint = false;
+        match termination {
+            Some(Termination::Halted) => {}
+            Some(Termination::LoopDetected { address }) => self.visits[address] += 1,
+            // A jump out of bounds still runs before it fails.
+            None | Some(Termination::JumpOutOfBounds { .. }) => {
+                self.record(address, before, out, echo)?
+            }
+        }
+
+        if let Some(termination) = termination {
+            self.stopped = Some(termination);
+            writeln!(out, "Program stopped: {}", termination)?;
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Adds the instruction just executed to the trace and the visit counts, and reports it.
+    fn record(
+        &mut self,
+        address: usize,
+        before: i32,
+        out: &mut dyn Write,
+        echo: bool,
+    ) -> Result<(), IoError> {
+        let instruction = self.processor.instructions()[address];
+        let accumulator = self.processor.accumulator();
+        self.visits[address] += 1;
+        self.trace.push(TraceEntry {
+            address,
+            instruction,
+            accumulator,
+        });
+
+        if echo {
+            writeln!(
+                out,
+                "{:>5}: {:<12} acc={}",
+                address, instruction, accumulator
+            )?;
+        }
+        if self.watching && accumulator != before {
+            writeln!(
+                out,
+                "acc: {} -> {} at address {}",
+                before, accumulator, address
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Carries out a command, returning whether the session should go on.
+    fn execute(&mut self, command: Command, out: &mut dyn Write) -> Result<bool, IoError> {
+        match command {
+            Command::Step(count) => {
+                for _ in 0..count {
+                    if !self.step(out, true)? {
+                        break;
+                    }
+                }
+            }
+            Command::Continue => loop {
+                let address = self.processor.instruction_pointer();
+                if self.breakpoints.contains(&address) && !self.at_breakpoint {
+                    writeln!(out, "Breakpoint at address {}", address)?;
+                    self.at_breakpoint = true;
+                    break;
+                }
+                if !self.step(out, false)? {
+                    break;
+                }
+            },
+            Command::Break(address) => {
+                self.breakpoints.insert(address);
+                writeln!(out, "Breakpoint set at address {}", address)?;
+            }
+            Command::Delete(address) => match self.breakpoints.remove(&address) {
+                true => writeln!(out, "Breakpoint removed from address {}", address)?,
+                false => writeln!(out, "No breakpoint at address {}", address)?,
+            },
+            Command::Watch => {
+                self.watching = !self.watching;
+                writeln!(
+                    out,
+                    "Watching accumulator: {}",
+                    if self.watching { "on" } else { "off" }
+                )?;
+            }
+            Command::Print => self.print(out)?,
+            Command::Trace => self.dump_trace(out)?,
+            Command::Help => writeln!(out, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn print(&self, out: &mut dyn Write) -> Result<(), IoError> {
+        let address = self.processor.instruction_pointer();
+        let next = match self.processor.instructions().get(address) {
+            Some(instruction) => instruction.to_string(),
+            None => String::from("(end of program)"),
+        };
+        let registers: Vec<String> = self
+            .processor
+            .registers()
+            .iter()
+            .enumerate()
+            .map(|(i, value)| format!("r{}={}", i, value))
+            .collect();
+        let breakpoints: Vec<String> = self.breakpoints.iter().map(|b| b.to_string()).collect();
+
+        writeln!(out, "ip={} next: {}", address, next)?;
+        writeln!(out, "registers: {}", registers.join(" "))?;
+        writeln!(out, "breakpoints: {}", breakpoints.join(" "))
+    }
+
+    fn dump_trace(&self, out: &mut dyn Write) -> Result<(), IoError> {
+        writeln!(out, " step  addr  instruction   acc")?;
+        for (step, entry) in self.trace.iter().enumerate() {
+            writeln!(
+                out,
+                "{:>5} {:>5}  {:<12} {:>5}",
+                step, entry.address, entry.instruction, entry.accumulator
+            )?;
+        }
+
+        writeln!(out, "Visits:")?;
+        for (address, count) in self.visits.iter().enumerate().filter(|(_, c)| **c > 0) {
+            writeln!(out, "{:>5}: {}", address, count)?;
+        }
+        if let Some(termination) = self.stopped {
+            writeln!(out, "Program stopped: {}", termination)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads debugger commands from `input` until it runs out or a `quit` command, writing results
+/// to `out`. An interactive session prompts for each command; otherwise each command is echoed
+/// so a scripted session's output can be read on its own.
+pub fn run_session(
+    processor: Processor,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    interactive: bool,
+) -> Result<(), IoError> {
+    let mut debugger = Debugger::new(processor);
+
+    loop {
+        if interactive {
+            write!(out, "(debug) ")?;
+            out.flush()?;
+        }
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !interactive {
+            writeln!(out, "> {}", line)?;
+        }
+
+        match line.parse() {
+            Ok(command) => {
+                if !debugger.execute(command, out)? {
+                    return Ok(());
+                }
+            }
+            Err(e) => writeln!(out, "{}", e)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: [&str; 9] = [
+        "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4", "acc +6",
+    ];
+
+    fn run_script(script: &str) -> String {
+        let mut processor = Processor::new();
+        processor.load(&SAMPLE).unwrap();
+        let mut out = vec![];
+
+        run_session(processor, &mut script.as_bytes(), &mut out, false).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn day_08_debugger_parses_commands() {
+        assert_eq!(Ok(Command::Step(1)), "s".parse());
+        assert_eq!(Ok(Command::Step(3)), "step 3".parse());
+        assert_eq!(Ok(Command::Break(7)), "b 7".parse());
+        assert_eq!(
+            Err(CommandError::MissingAddress("break".to_string())),
+            "break".parse::<Command>()
+        );
+        assert_eq!(
+            Err(CommandError::InvalidNumber("step x".to_string())),
+            "step x".parse::<Command>()
+        );
+    }
+
+    #[test]
+    fn day_08_debugger_single_steps() {
+        let output = run_script("step 2\nprint\n");
+
+        assert_eq!(
+            "> step 2\n    0: nop +0       acc=0\n    1: acc +1       acc=1\n\
+             > print\nip=2 next: jmp +4\nregisters: r0=1 r1=0 r2=0 r3=0\nbreakpoints: \n",
+            output
+        );
+    }
+
+    #[test]
+    fn day_08_debugger_stops_at_breakpoint() {
+        let output = run_script("break 3\ncontinue\nprint\n");
+
+        assert!(output.contains("Breakpoint at address 3\n"));
+        assert!(output.contains("ip=3 next: acc +3\n"));
+    }
+
+    #[test]
+    fn day_08_debugger_stops_at_breakpoint_it_starts_on() {
+        let output = run_script("break 0\nbreak 1\ncontinue\ncontinue\ncontinue\nprint\n");
+
+        assert!(output.contains(
+            "> continue\nBreakpoint at address 0\n> continue\nBreakpoint at address 1\n"
+        ));
+        // The loop comes back round to address 1 before it would run again.
+        assert_eq!(2, output.matches("Breakpoint at address 1\n").count());
+        assert!(output.contains("ip=1 next: acc +1\n"));
+    }
+
+    #[test]
+    fn day_08_debugger_traces_jump_out_of_bounds() {
+        let mut processor = Processor::new();
+        processor.load(&["acc +2", "jmp +5"]).unwrap();
+        let mut out = vec![];
+
+        run_session(
+            processor,
+            &mut "continue\ntrace\n".as_bytes(),
+            &mut out,
+            false,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Program stopped: jump out of bounds"));
+        assert!(output.contains("    1     1  jmp +5           2\n"));
+        assert!(output.contains("Visits:\n    0: 1\n    1: 1\n"));
+    }
+
+    #[test]
+    fn day_08_debugger_watches_accumulator() {
+        let output = run_script("watch\nstep 3\n");
+
+        assert!(output.contains("acc: 0 -> 1 at address 1\n"));
+        assert!(!output.contains("at address 0\n"));
+    }
+
+    #[test]
+    fn day_08_debugger_dumps_trace_with_visit_counts() {
+        let output = run_script("continue\ntrace\nquit\nstep\n");
+
+        assert!(output.contains("Program stopped: loop detected at address 1\n"));
+        assert!(output.contains("    6     4  jmp -3           5\n"));
+        assert!(output.contains("Visits:\n    0: 1\n    1: 2\n    2: 1\n"));
+        assert!(!output.contains("> step"));
+    }
+}
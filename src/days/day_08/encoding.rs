@@ -0,0 +1,160 @@
+use std::convert::TryInto;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::processor::{Instruction, INSTRUCTION_SET, REGISTER_COUNT};
+
+/// Marks the start of a compiled program.
+const MAGIC: &[u8] = b"AOC8";
+const VERSION: u8 = 1;
+/// Each instruction takes one byte for its opcode, one for its register and four for its
+/// argument.
+const INSTRUCTION_SIZE: usize = 6;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    MissingHeader,
+    UnsupportedVersion(u8),
+    Truncated { address: usize },
+    UnknownOpcode { address: usize, opcode: u8 },
+    InvalidRegister { address: usize, register: u8 },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::MissingHeader => write!(f, "Not a compiled program"),
+            Self::UnsupportedVersion(version) => write!(f, "Unsupported version: {}", version),
+            Self::Truncated { address } => {
+                write!(f, "Instruction at address {} is truncated", address)
+            }
+            Self::UnknownOpcode { address, opcode } => {
+                write!(f, "Unknown opcode {} at address {}", opcode, address)
+            }
+            Self::InvalidRegister { address, register } => {
+                write!(f, "Invalid register {} at address {}", register, address)
+            }
+        }
+    }
+}
+
+/// Returns whether the bytes start like a compiled program.
+pub fn is_encoded(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Compiles a program into its binary form: a header followed by each instruction in turn. An
+/// opcode is stored as its position in `INSTRUCTION_SET`, and the argument as four little-endian
+/// bytes.
+pub fn encode(instructions: &[Instruction]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+
+    for instruction in instructions {
+        let opcode = INSTRUCTION_SET
+            .iter()
+            .position(|spec| spec.opcode == instruction.opcode)
+            .unwrap();
+        bytes.push(opcode as u8);
+        bytes.push(instruction.register as u8);
+        bytes.extend_from_slice(&instruction.argument.to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Reads a program compiled by `encode()`.
+pub fn decode(bytes: &[u8]) -> Result<Vec<Instruction>, DecodeError> {
+    let body = bytes
+        .strip_prefix(MAGIC)
+        .ok_or(DecodeError::MissingHeader)?;
+    let (version, body) = body.split_first().ok_or(DecodeError::MissingHeader)?;
+    if *version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(*version));
+    }
+
+    body.chunks(INSTRUCTION_SIZE)
+        .enumerate()
+        .map(|(address, chunk)| {
+            if chunk.len() < INSTRUCTION_SIZE {
+                return Err(DecodeError::Truncated { address });
+            }
+
+            let spec =
+                INSTRUCTION_SET
+                    .get(chunk[0] as usize)
+                    .ok_or(DecodeError::UnknownOpcode {
+                        address,
+                        opcode: chunk[0],
+                    })?;
+            let register = chunk[1] as usize;
+            if register >= REGISTER_COUNT {
+                return Err(DecodeError::InvalidRegister {
+                    address,
+                    register: chunk[1],
+                });
+            }
+
+            Ok(Instruction {
+                register,
+                ..Instruction::new(
+                    spec.opcode,
+                    i32::from_le_bytes(chunk[2..].try_into().unwrap()),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::assembler::assemble;
+    use super::*;
+
+    #[test]
+    fn day_08_encodes_instructions_compactly() {
+        let program = assemble(&["nop +0", "acc r2 -2", "jmp +258"]).unwrap();
+
+        assert_eq!(
+            b"AOC8\x01\
+              \x00\x00\x00\x00\x00\x00\
+              \x01\x02\xfe\xff\xff\xff\
+              \x02\x00\x02\x01\x00\x00"
+                .to_vec(),
+            encode(&program)
+        );
+    }
+
+    #[test]
+    fn day_08_decoding_round_trips() {
+        let program = assemble(&["nop +0", "acc r3 +1", "jmp -2", "acc -2147483648"]).unwrap();
+
+        let bytes = encode(&program);
+
+        assert!(is_encoded(&bytes));
+        assert_eq!(Ok(program), decode(&bytes));
+    }
+
+    #[test]
+    fn day_08_rejects_malformed_programs() {
+        assert_eq!(Err(DecodeError::MissingHeader), decode(b"nop +0"));
+        assert_eq!(Err(DecodeError::UnsupportedVersion(9)), decode(b"AOC8\x09"));
+        assert_eq!(
+            Err(DecodeError::Truncated { address: 1 }),
+            decode(b"AOC8\x01\x00\x00\x00\x00\x00\x00\x01\x00")
+        );
+        assert_eq!(
+            Err(DecodeError::UnknownOpcode {
+                address: 0,
+                opcode: 7
+            }),
+            decode(b"AOC8\x01\x07\x00\x00\x00\x00\x00")
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidRegister {
+                address: 0,
+                register: 4
+            }),
+            decode(b"AOC8\x01\x01\x04\x00\x00\x00\x00")
+        );
+    }
+}
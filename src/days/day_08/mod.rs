@@ -0,0 +1,109 @@
+mod analysis;
+mod assembler;
+mod debugger;
+mod encoding;
+mod processor;
+mod repair;
+
+use std::io::{BufRead, Error as IoError, Write};
+
+use self::analysis::Analysis;
+use self::assembler::{assemble, disassemble, AssemblyError};
+use self::encoding::DecodeError;
+use self::processor::{Processor, Termination};
+
+pub fn part_one(data: &[&str]) {
+    let mut processor = Processor::new();
+    if let Ok(()) = processor.load(data) {
+        match processor.run() {
+            Termination::LoopDetected { address } => {
+                println!("Encountered infinite loop at address {}", address)
+            }
+            termination => println!("Program stopped without looping: {}", termination),
+        }
+        println!("Accumulator: {}", processor.accumulator());
+    } else {
+        println!("Loading instructions failed");
+    }
+}
+
+pub fn part_two(data: &[&str]) {
+    let mut processor = Processor::new();
+    if let Err(e) = processor.load(data) {
+        println!("Loading instructions failed: {}", e);
+        return;
+    }
+
+    let repairs = repair::find_repairs(processor.instructions());
+    if repairs.is_empty() {
+        println!("No single nop/jmp change makes the program halt");
+    }
+    for repair in repairs {
+        println!(
+            "Changing address {} to {} halts the program",
+            repair.address,
+            repair.replacement.opcode.spec().mnemonic
+        );
+        println!("Accumulator: {}", repair.accumulator);
+    }
+}
+
+/// Loads the program and runs a debugger session on it, reading commands from `input`.
+pub fn debug(
+    data: &[&str],
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    interactive: bool,
+) -> Result<(), IoError> {
+    let mut processor = Processor::new();
+    if let Err(e) = processor.load(data) {
+        writeln!(out, "Loading instructions failed: {}", e)?;
+        return Ok(());
+    }
+
+    debugger::run_session(processor, input, out, interactive)
+}
+
+/// Loads the program and writes a report of its control flow, or the control-flow graph in DOT
+/// format.
+pub fn analyze(data: &[&str], dot: bool, out: &mut dyn Write) -> Result<(), IoError> {
+    let mut processor = Processor::new();
+    if let Err(e) = processor.load(data) {
+        writeln!(out, "Loading instructions failed: {}", e)?;
+        return Ok(());
+    }
+
+    let analysis = Analysis::new(processor.instructions());
+    match dot {
+        true => write!(out, "{}", analysis.to_dot()),
+        false => write!(out, "{}", analysis),
+    }
+}
+
+/// Assembles the source code into a compiled program.
+pub fn compile(data: &[&str]) -> Result<Vec<u8>, AssemblyError> {
+    Ok(encoding::encode(&assemble(data)?))
+}
+
+/// Returns whether the bytes hold a compiled program rather than source code.
+pub fn is_compiled(bytes: &[u8]) -> bool {
+    encoding::is_encoded(bytes)
+}
+
+/// Turns a compiled program back into source code.
+pub fn decompile(bytes: &[u8]) -> Result<Vec<String>, DecodeError> {
+    Ok(disassemble(&encoding::decode(bytes)?))
+}
+
+/// Assembles the source code and writes it back out with every jump going to a label.
+pub fn list(data: &[&str], out: &mut dyn Write) -> Result<(), IoError> {
+    match assemble(data) {
+        Ok(program) => {
+            for line in disassemble(&program) {
+                writeln!(out, "{}", line)?;
+            }
+            Ok(())
+        }
+        Err(e) => writeln!(out, "Loading instructions failed: {}", e),
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use super::assembler::{assemble, AssemblyError};
+
+/// The number of registers in the processor. Register 0 is the accumulator.
+pub const REGISTER_COUNT: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Nop,
+    Acc,
+    Jmp,
+}
+
+/// Where the processor goes after executing an instruction.
+enum Flow {
+    /// Moves on to the next instruction.
+    Next,
+    /// Moves the instruction pointer by the given offset.
+    Jump(i32),
+}
+
+/// Describes one opcode of the instruction set.
+pub struct OpcodeSpec {
+    pub opcode: Opcode,
+    pub mnemonic: &'static str,
+    /// Applies the instruction's effect on the registers.
+    execute: fn(&mut [i32; REGISTER_COUNT], &Instruction),
+    /// Decides where control goes next. This can't depend on the registers, so a program's
+    /// control flow can be analyzed without running it.
+    flow: fn(&Instruction) -> Flow,
+}
+
+/// The instruction set understood by the processor. Adding an opcode only requires adding a
+/// variant to `Opcode` and an entry here.
+pub const INSTRUCTION_SET: [OpcodeSpec; 3] = [
+    OpcodeSpec {
+        opcode: Opcode::Nop,
+        mnemonic: "nop",
+        execute: |_, _| {},
+        flow: |_| Flow::Next,
+    },
+    OpcodeSpec {
+        opcode: Opcode::Acc,
+        mnemonic: "acc",
+        execute: |registers, instruction| registers[instruction.register] += instruction.argument,
+        flow: |_| Flow::Next,
+    },
+    OpcodeSpec {
+        opcode: Opcode::Jmp,
+        mnemonic: "jmp",
+        execute: |_, _| {},
+        flow: |instruction| Flow::Jump(instruction.argument),
+    },
+];
+
+impl Opcode {
+    pub fn spec(&self) -> &'static OpcodeSpec {
+        INSTRUCTION_SET
+            .iter()
+            .find(|spec| spec.opcode == *self)
+            .unwrap()
+    }
+}
+
+/// A single instruction. `register` is only meaningful for opcodes that use one, and defaults
+/// to the accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub register: usize,
+    pub argument: i32,
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, argument: i32) -> Self {
+        Self {
+            opcode,
+            register: 0,
+            argument,
+        }
+    }
+
+    /// Returns the address control goes to after this instruction runs at the given address.
+    /// The result may lie outside the program.
+    pub fn successor(&self, address: usize) -> i64 {
+        let offset = match (self.opcode.spec().flow)(self) {
+            Flow::Next => 1,
+            Flow::Jump(offset) => offset,
+        };
+        address as i64 + offset as i64
+    }
+
+    /// Returns how much this instruction changes each register. Every opcode adds to its
+    /// register, so running an instruction adds its effect to the registers no matter what they
+    /// hold.
+    pub fn effect(&self) -> [i32; REGISTER_COUNT] {
+        let mut registers = [0; REGISTER_COUNT];
+        (self.opcode.spec().execute)(&mut registers, self);
+        registers
+    }
+}
+
+impl Display for Instruction {
+    /// Writes the instruction the way it's parsed, naming the register only when it isn't the
+    /// accumulator.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mnemonic = self.opcode.spec().mnemonic;
+        let text = match self.register {
+            0 => format!("{} {:+}", mnemonic, self.argument),
+            r => format!("{} r{} {:+}", mnemonic, r, self.argument),
+        };
+        f.pad(&text)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnrecognizedInstruction(String),
+    InvalidArgument(String),
+    InvalidRegister(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnrecognizedInstruction(instruction) => {
+                write!(f, "Unrecognized instruction: {}", instruction)
+            }
+            Self::InvalidArgument(instruction) => write!(f, "Invalid argument: {}", instruction),
+            Self::InvalidRegister(instruction) => write!(f, "Invalid register: {}", instruction),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    /// Parses an instruction such as `acc +1`. An instruction can name a register before its
+    /// argument, as in `acc r2 -3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+
+        let spec = parts
+            .first()
+            .and_then(|mnemonic| {
+                INSTRUCTION_SET
+                    .iter()
+                    .find(|spec| spec.mnemonic == *mnemonic)
+            })
+            .ok_or_else(|| ParseError::UnrecognizedInstruction(s.to_owned()))?;
+
+        let (register, argument) = match parts[1..] {
+            [argument] => (0, argument),
+            [register, argument] => (parse_register(register, s)?, argument),
+            _ => return Err(ParseError::InvalidArgument(s.to_owned())),
+        };
+
+        match argument.parse() {
+            Ok(argument) => Ok(Self {
+                register,
+                ..Self::new(spec.opcode, argument)
+            }),
+            Err(_) => Err(ParseError::InvalidArgument(s.to_owned())),
+        }
+    }
+}
+
+fn parse_register(register: &str, instruction: &str) -> Result<usize, ParseError> {
+    register
+        .strip_prefix('r')
+        .and_then(|index| index.parse().ok())
+        .filter(|index| *index < REGISTER_COUNT)
+        .ok_or_else(|| ParseError::InvalidRegister(instruction.to_owned()))
+}
+
+/// Why the processor stopped running a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// The instruction pointer moved to just past the last instruction.
+    Halted,
+    /// The instruction at the given address was about to run a second time.
+    LoopDetected { address: usize },
+    /// The jump at the given address targeted an address outside the program.
+    JumpOutOfBounds { address: usize, target: i64 },
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Halted => write!(f, "halted"),
+            Self::LoopDetected { address } => write!(f, "loop detected at address {}", address),
+            Self::JumpOutOfBounds { address, target } => write!(
+                f,
+                "jump out of bounds at address {} (target {})",
+                address, target
+            ),
+        }
+    }
+}
+
+pub struct Processor {
+    registers: [i32; REGISTER_COUNT],
+    instruction_pointer: usize,
+    instructions: Vec<Instruction>,
+    addresses_visited: HashSet<usize>,
+}
+
+impl Processor {
+    pub fn new() -> Self {
+        Self::with_instructions(vec![])
+    }
+
+    pub fn with_instructions(instructions: Vec<Instruction>) -> Self {
+        Self {
+            registers: [0; REGISTER_COUNT],
+            instruction_pointer: 0,
+            instructions,
+            addresses_visited: HashSet::new(),
+        }
+    }
+
+    /// Assembles the source code and loads the resulting program.
+    pub fn load<T: AsRef<str>>(&mut self, source: &[T]) -> Result<(), AssemblyError> {
+        self.instructions = assemble(source)?;
+
+        Ok(())
+    }
+
+    /// Executes the instruction at the instruction pointer. Returns why the program stopped if
+    /// it can't continue, in which case nothing is executed.
+    pub fn step(&mut self) -> Option<Termination> {
+        let address = self.instruction_pointer;
+        let instruction = match self.instructions.get(address) {
+            Some(instruction) => *instruction,
+            None => return Some(Termination::Halted),
+        };
+
+        if !self.addresses_visited.insert(address) {
+            return Some(Termination::LoopDetected { address });
+        }
+
+        (instruction.opcode.spec().execute)(&mut self.registers, &instruction);
+
+        let target = instruction.successor(address);
+        if target < 0 || target > self.instructions.len() as i64 {
+            return Some(Termination::JumpOutOfBounds { address, target });
+        }
+        self.instruction_pointer = target as usize;
+
+        None
+    }
+
+    /// Runs the program until it stops.
+    pub fn run(&mut self) -> Termination {
+        loop {
+            if let Some(termination) = self.step() {
+                return termination;
+            }
+        }
+    }
+
+    pub fn accumulator(&self) -> i32 {
+        self.registers[0]
+    }
+
+    pub fn registers(&self) -> &[i32; REGISTER_COUNT] {
+        &self.registers
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_08_parses_nop() -> Result<(), ParseError> {
+        let instruction: Instruction = "nop +0".parse()?;
+
+        assert_eq!(instruction, Instruction::new(Opcode::Nop, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_parses_acc() -> Result<(), ParseError> {
+        let instruction: Instruction = "acc +1".parse()?;
+
+        assert_eq!(instruction, Instruction::new(Opcode::Acc, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_parses_jmp() -> Result<(), ParseError> {
+        let instruction: Instruction = "jmp +3".parse()?;
+
+        assert_eq!(instruction, Instruction::new(Opcode::Jmp, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_parses_register() -> Result<(), ParseError> {
+        let instruction: Instruction = "acc r2 -7".parse()?;
+
+        assert_eq!(
+            instruction,
+            Instruction {
+                opcode: Opcode::Acc,
+                register: 2,
+                argument: -7
+            }
+        );
+        assert_eq!(
+            Err(ParseError::InvalidRegister("acc r9 +1".to_string())),
+            "acc r9 +1".parse::<Instruction>()
+        );
+        assert_eq!(
+            Err(ParseError::InvalidArgument("jmp".to_string())),
+            "jmp".parse::<Instruction>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_displays_instructions_as_parsed() -> Result<(), ParseError> {
+        for text in &["nop +0", "acc -99", "jmp +4", "acc r3 +12"] {
+            let instruction: Instruction = text.parse()?;
+
+            assert_eq!(*text, instruction.to_string());
+            assert_eq!(instruction, instruction.to_string().parse()?);
+        }
+        assert_eq!(
+            "acc +1  |",
+            format!("{:<8}|", Instruction::new(Opcode::Acc, 1))
+        );
+
+        Ok(())
+    }
+
+    fn get_sample_instructions() -> Vec<String> {
+        vec![
+            "nop +0".to_string(),
+            "acc +1".to_string(),
+            "jmp +4".to_string(),
+            "acc +3".to_string(),
+            "jmp -3".to_string(),
+            "acc -99".to_string(),
+            "acc +1".to_string(),
+            "jmp -4".to_string(),
+            "acc +6".to_string(),
+        ]
+    }
+
+    #[test]
+    fn day_08_correct_value_in_accumulator_upon_repeated_instruction() -> Result<(), AssemblyError>
+    {
+        let sample_instructions = get_sample_instructions();
+
+        let mut processor = Processor::new();
+        processor.load(&sample_instructions)?;
+
+        assert_eq!(processor.run(), Termination::LoopDetected { address: 1 });
+        assert_eq!(processor.accumulator(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_halts_after_last_instruction() -> Result<(), AssemblyError> {
+        let mut processor = Processor::new();
+        processor.load(&["acc +2", "acc r1 +5", "nop -1"])?;
+
+        assert_eq!(None, processor.step());
+        assert_eq!(None, processor.step());
+        assert_eq!(None, processor.step());
+        assert_eq!(Some(Termination::Halted), processor.step());
+        assert_eq!(processor.accumulator(), 2);
+        assert_eq!(processor.registers[1], 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_08_detects_jump_out_of_bounds() -> Result<(), AssemblyError> {
+        let mut processor = Processor::new();
+        processor.load(&["nop +0", "jmp -2"])?;
+
+        assert_eq!(
+            processor.run(),
+            Termination::JumpOutOfBounds {
+                address: 1,
+                target: -1
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use super::processor::{Instruction, Opcode};
+
+/// A single instruction change that makes a looping program halt.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Repair {
+    pub address: usize,
+    pub replacement: Instruction,
+    pub accumulator: i32,
+}
+
+/// Swaps a `nop` for a `jmp` or the other way around.
+fn flip(instruction: &Instruction) -> Option<Instruction> {
+    let opcode = match instruction.opcode {
+        Opcode::Nop => Opcode::Jmp,
+        Opcode::Jmp => Opcode::Nop,
+        _ => return None,
+    };
+
+    Some(Instruction {
+        opcode,
+        ..*instruction
+    })
+}
+
+/// Finds the addresses from which control eventually reaches the end of the program, along with
+/// how much the accumulator gains on the way there.
+///
+/// Every address has exactly one successor, so walking the control-flow graph's edges backward
+/// from the end visits each address at most once, and each address gains its own effect plus
+/// whatever its successor gains.
+fn find_terminating(instructions: &[Instruction]) -> Vec<Option<i32>> {
+    let end = instructions.len();
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]; end + 1];
+    for (address, instruction) in instructions.iter().enumerate() {
+        let target = instruction.successor(address);
+        if (0..=end as i64).contains(&target) {
+            predecessors[target as usize].push(address);
+        }
+    }
+
+    let mut terminating = vec![None; end + 1];
+    let mut pending = vec![end];
+    terminating[end] = Some(0);
+    while let Some(address) = pending.pop() {
+        let gain = terminating[address].unwrap();
+        for &predecessor in &predecessors[address] {
+            if terminating[predecessor].is_none() {
+                terminating[predecessor] = Some(instructions[predecessor].effect()[0] + gain);
+                pending.push(predecessor);
+            }
+        }
+    }
+
+    terminating
+}
+
+/// Finds every single `nop`/`jmp` flip that makes the program halt, in linear time.
+///
+/// Only instructions the original program actually runs can change its behavior. Flipping one
+/// of them fixes the program exactly when its new successor reaches the end in the original
+/// graph: that path can't lead back through the flipped instruction, or the original program
+/// would have halted too. The repaired program's accumulator is then what the original had
+/// gained on reaching the flipped instruction, plus what the rest of the path gains. Returns
+/// nothing if the program already halts.
+pub fn find_repairs(instructions: &[Instruction]) -> Vec<Repair> {
+    let terminating = find_terminating(instructions);
+    if terminating[0].is_some() {
+        return vec![];
+    }
+
+    let mut executed = vec![None; instructions.len()];
+    let mut accumulator = 0;
+    let mut address = 0;
+    while address < instructions.len() && executed[address].is_none() {
+        executed[address] = Some(accumulator);
+        accumulator += instructions[address].effect()[0];
+        match instructions[address].successor(address) {
+            target if target >= 0 => address = target as usize,
+            _ => break,
+        }
+    }
+
+    (0..instructions.len())
+        .filter_map(|address| {
+            let before = executed[address]?;
+            let replacement = flip(&instructions[address])?;
+            let target = replacement.successor(address);
+            if target < 0 || target as usize > instructions.len() {
+                return None;
+            }
+            let after = terminating[target as usize]?;
+
+            Some(Repair {
+                address,
+                replacement,
+                accumulator: before + replacement.effect()[0] + after,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn day_08_repairs_sample_program() {
+        let instructions = parse(&[
+            "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
+            "acc +6",
+        ]);
+
+        assert_eq!(
+            vec![Repair {
+                address: 7,
+                replacement: Instruction::new(Opcode::Nop, -4),
+                accumulator: 8
+            }],
+            find_repairs(&instructions)
+        );
+    }
+
+    #[test]
+    fn day_08_reports_every_repair() {
+        let instructions = parse(&["nop +2", "jmp +0", "acc +1"]);
+
+        let repairs = find_repairs(&instructions);
+
+        assert_eq!(2, repairs.len());
+        assert_eq!(0, repairs[0].address);
+        assert_eq!(1, repairs[0].accumulator);
+        assert_eq!(1, repairs[1].address);
+        assert_eq!(1, repairs[1].accumulator);
+    }
+
+    #[test]
+    fn day_08_needs_no_repair_for_halting_program() {
+        assert!(find_repairs(&parse(&["acc +1", "jmp +1"])).is_empty());
+    }
+
+    #[test]
+    fn day_08_finds_addresses_that_reach_the_end() {
+        let instructions = parse(&["jmp +2", "jmp +0", "nop +0", "jmp -3"]);
+        let gaining = parse(&["acc +2", "jmp +2", "acc +5", "acc -1"]);
+
+        assert_eq!(
+            vec![None, None, None, None, Some(0)],
+            find_terminating(&instructions)
+        );
+        assert_eq!(
+            vec![Some(1), Some(-1), Some(4), Some(-1), Some(0)],
+            find_terminating(&gaining)
+        );
+    }
+}
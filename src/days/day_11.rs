@@ -1,113 +1,169 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::io::Error as IoError;
+use std::path::Path;
 
+use super::automaton::{Automaton, Coord, Neighborhood};
 // Kind of a cheat. I created this type in a different project.
 use super::grid::Grid;
+use super::life_rule::LifeRule;
+use super::render::{save_frames, Frame};
+use super::simulation::{simulate, Outcome, Simulation};
+
+/// Gives up on seating that hasn't settled after this many rounds.
+const MAX_ROUNDS: usize = 1000;
 
 pub fn part_one(data: &[&str]) {
-    let mut grid = parse_into_grid(data);
-    while tick(&mut grid, 4, false) {}
-    let occupied_seats = count_occupied_seats(&grid);
-    println!("Occupied seats: {}", occupied_seats);
+    let mut area = WaitingArea::new(parse_into_grid(data), ADJACENT_RULE.parse().unwrap(), false);
+    report(&mut area);
 }
 
 pub fn part_two(data: &[&str]) {
-    let mut grid = parse_into_grid(data);
-    while tick(&mut grid, 5, true) {}
-    let occupied_seats = count_occupied_seats(&grid);
-    println!("Occupied seats: {}", occupied_seats);
+    let mut area = WaitingArea::new(parse_into_grid(data), VISIBLE_RULE.parse().unwrap(), true);
+    report(&mut area);
 }
 
-fn parse_into_grid(data: &[&str]) -> Grid {
-    let columns = data[0].len() as u32;
-    let rows = data.len() as u32;
+pub fn dump_frames(data: &[&str], dir: &Path, rounds: usize) -> Result<(), IoError> {
+    let mut area = WaitingArea::new(parse_into_grid(data), ADJACENT_RULE.parse().unwrap(), false);
+    save_frames(dir, "part_one", &area.frames(rounds))?;
 
-    Grid::new(
-        rows,
-        columns,
-        data.iter().map(|s| s.chars()).flatten().collect(),
-    )
-    .unwrap()
+    let mut area = WaitingArea::new(parse_into_grid(data), VISIBLE_RULE.parse().unwrap(), true);
+    save_frames(dir, "part_two", &area.frames(rounds))
 }
 
-fn tick(grid: &mut Grid, tolerance: u32, visible: bool) -> bool {
-    let previous_hash = calculate_hash(&grid);
+fn report(area: &mut WaitingArea) {
+    match simulate(area, MAX_ROUNDS) {
+        Outcome::FixedPoint { .. } => {
+            println!("Occupied seats: {}", count_occupied_seats(&area.grid))
+        }
+        Outcome::Cycle { start, period } => println!(
+            "Seating never settles: it repeats every {} rounds from round {}",
+            period, start
+        ),
+        Outcome::Unsettled { steps } => {
+            println!("Seating hasn't settled after {} rounds", steps)
+        }
+    }
+}
+
+/// An empty seat becomes occupied when no neighboring seat is occupied, and an occupied seat is
+/// vacated when four or more adjacent seats are occupied.
+const ADJACENT_RULE: &str = "B0/S0123";
+
+/// Like `ADJACENT_RULE`, but tolerating four occupied seats in view.
+const VISIBLE_RULE: &str = "B0/S01234";
 
-    let analysis = match visible {
-        false => analyze_neighbors(&grid),
-        true => analyze_visible_neighbors(&grid),
-    };
+/// Runs the seating automaton, in which occupied seats are active cells and the floor is left
+/// out of the neighborhood entirely.
+struct WaitingArea {
+    grid: Grid,
+    automaton: Automaton<LifeRule, 2>,
+}
+
+impl WaitingArea {
+    fn new(grid: Grid, rule: LifeRule, visible: bool) -> Self {
+        let mut edges: HashMap<Coord<2>, Vec<Coord<2>>> = HashMap::new();
+        let mut occupied = vec![];
 
-    for (r, c, state, neighbor_count) in analysis {
-        match state {
-            'L' => {
-                if neighbor_count == 0 {
-                    grid.set(r, c, '#');
+        for r in 0..grid.get_rows() {
+            for c in 0..grid.get_columns() {
+                let state = grid.get(r, c);
+                if state == '.' {
+                    continue;
                 }
-            }
-            '#' => {
-                if neighbor_count >= tolerance {
-                    grid.set(r, c, 'L');
+                if state == '#' {
+                    occupied.push(to_coord(r, c));
                 }
+
+                let neighbors = match visible {
+                    false => grid.neighbors(r, c),
+                    true => grid.visible_neighbors(r, c),
+                };
+                edges.insert(
+                    to_coord(r, c),
+                    neighbors
+                        .into_iter()
+                        .filter(|(r, c)| grid.get(*r, *c) != '.')
+                        .map(|(r, c)| to_coord(r, c))
+                        .collect(),
+                );
             }
-            _ => {}
         }
-    }
 
-    calculate_hash(&grid) != previous_hash
-}
+        let automaton = Automaton::new(rule, Neighborhood::Graph(edges), occupied);
 
-fn calculate_hash<T: Hash>(t: &T) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
-}
+        Self { grid, automaton }
+    }
 
-fn count_occupied_neighbors(grid: &Grid, row: u32, column: u32) -> u32 {
-    grid.neighbors(row, column)
-        .iter()
-        .filter(|&&state| state == '#')
-        .count() as u32
-}
+    /// Advances the seating by one round and returns whether any seat changed state.
+    fn tick(&mut self) -> bool {
+        let previous = self.automaton.active().clone();
+        self.automaton.tick();
+
+        for [r, c] in previous.symmetric_difference(self.automaton.active()) {
+            let state = match self.automaton.is_active(&[*r, *c]) {
+                true => '#',
+                false => 'L',
+            };
+            self.grid.set(*r as u32, *c as u32, state);
+        }
 
-fn count_nearest_visible_occupied_neighbors(grid: &Grid, row: u32, column: u32) -> u32 {
-    grid.visible_neighbors(row, column)
-        .iter()
-        .filter(|&&state| state == '#')
-        .count() as u32
-}
+        &previous != self.automaton.active()
+    }
 
-fn count_occupied_seats(grid: &Grid) -> u32 {
-    grid.count_cells_in_state('#')
+    /// Runs the given number of rounds, returning a frame and a text rendering of each.
+    fn frames(&mut self, rounds: usize) -> Vec<(Frame, Option<String>)> {
+        let mut frames = vec![(draw_grid(&self.grid), Some(self.grid.to_string()))];
+        for _ in 0..rounds {
+            self.tick();
+            frames.push((draw_grid(&self.grid), Some(self.grid.to_string())));
+        }
+        frames
+    }
 }
 
-fn analyze_neighbors(grid: &Grid) -> Vec<(u32, u32, char, u32)> {
-    let mut results = vec![];
+/// Draws the floor in dark gray, empty seats in green and occupied seats in red.
+fn draw_grid(grid: &Grid) -> Frame {
+    let mut frame = Frame::new(grid.get_columns() as usize, grid.get_rows() as usize);
 
     for r in 0..grid.get_rows() {
         for c in 0..grid.get_columns() {
-            results.push((r, c, grid.get(r, c), count_occupied_neighbors(&grid, r, c)));
+            let color = match grid.get(r, c) {
+                'L' => [40, 160, 40],
+                '#' => [220, 50, 40],
+                _ => [40, 40, 40],
+            };
+            frame.set(c as usize, r as usize, color);
         }
     }
 
-    results
+    frame
 }
 
-fn analyze_visible_neighbors(grid: &Grid) -> Vec<(u32, u32, char, u32)> {
-    let mut results = vec![];
+impl Simulation for WaitingArea {
+    type State = Grid;
 
-    for r in 0..grid.get_rows() {
-        for c in 0..grid.get_columns() {
-            results.push((
-                r,
-                c,
-                grid.get(r, c),
-                count_nearest_visible_occupied_neighbors(&grid, r, c),
-            ));
-        }
+    fn state(&self) -> Grid {
+        self.grid.clone()
     }
 
-    results
+    fn step(&mut self) {
+        self.tick();
+    }
+}
+
+fn to_coord(row: u32, column: u32) -> Coord<2> {
+    [row as i64, column as i64]
+}
+
+fn parse_into_grid(data: &[&str]) -> Grid {
+    let columns = data[0].len() as u32;
+    let rows = data.len() as u32;
+
+    Grid::new(rows, columns, data.iter().flat_map(|s| s.chars()).collect()).unwrap()
+}
+
+fn count_occupied_seats(grid: &Grid) -> u32 {
+    grid.count_cells_in_state('#')
 }
 
 #[cfg(test)]
@@ -117,8 +173,13 @@ mod test {
     #[test]
     fn day_11_counts_occupied_neighbors() {
         let grid = Grid::new(3, 3, "LL#..###L".chars().collect()).unwrap();
+        let seated = Grid::new(3, 3, "LL#.L###L".chars().collect()).unwrap();
+        let area = WaitingArea::new(grid, ADJACENT_RULE.parse().unwrap(), false);
+        let seated = WaitingArea::new(seated, ADJACENT_RULE.parse().unwrap(), false);
 
-        assert_eq!(4, count_occupied_neighbors(&grid, 1, 1));
+        // The floor isn't part of the automaton, so it has no neighbors.
+        assert_eq!(0, area.automaton.active_neighbors(&[1, 1]));
+        assert_eq!(4, seated.automaton.active_neighbors(&[1, 1]));
     }
 
     #[test]
@@ -130,7 +191,7 @@ mod test {
 
     #[test]
     fn day_11_fills_all_seats_from_start() {
-        let mut grid: Grid = Grid::new(
+        let grid: Grid = Grid::new(
             10,
             10,
             "L.LL.LL.LLLLLLLLL.LLL.L.L..L..LLLL.LL.LLL.LL.LL.LLL.LLLLL.LL..L.L.....LLLLLLLLLLL.LLLLLL.LL.LLLLL.LL".chars().collect()).unwrap();
@@ -139,15 +200,17 @@ mod test {
             10,
             "#.##.##.#########.###.#.#..#..####.##.###.##.##.###.#####.##..#.#.....###########.######.##.#####.##".chars().collect()).unwrap();
 
-        let state_changed = tick(&mut grid, 4, false);
+        let mut area = WaitingArea::new(grid, ADJACENT_RULE.parse().unwrap(), false);
+
+        let state_changed = area.tick();
 
-        assert_eq!(expected, grid);
+        assert_eq!(expected, area.grid);
         assert!(state_changed);
     }
 
     #[test]
     fn day_11_correctly_updates_second_round() {
-        let mut grid: Grid = Grid::new(
+        let grid: Grid = Grid::new(
             10,
             10,
             "#.##.##.#########.###.#.#..#..####.##.###.##.##.###.#####.##..#.#.....###########.######.##.#####.##".chars().collect()).unwrap();
@@ -156,20 +219,36 @@ mod test {
             10,
             "#.LL.L#.###LLLLLL.L#L.L.L..L..#LLL.LL.L##.LL.LL.LL#.LLLL#.##..L.L.....#LLLLLLLL##.LLLLLL.L#.#LLLL.##".chars().collect()).unwrap();
 
-        let state_changed = tick(&mut grid, 4, false);
+        let mut area = WaitingArea::new(grid, ADJACENT_RULE.parse().unwrap(), false);
 
-        assert_eq!(expected, grid);
+        let state_changed = area.tick();
+
+        assert_eq!(expected, area.grid);
         assert!(state_changed);
     }
 
     #[test]
     fn day_11_detects_no_change_in_grid() {
-        let mut grid: Grid = Grid::new(
+        let grid: Grid = Grid::new(
             10,
             10,
             "#.#L.L#.###LLL#LL.L#L.#.L..#..#L##.##.L##.#L.LL.LL#.#L#L#.##..L.L.....#L#L##L#L##.LLLLLL.L#.#L#L#.##".chars().collect()).unwrap();
 
-        assert_eq!(false, tick(&mut grid, 4, false));
+        let mut area = WaitingArea::new(grid, ADJACENT_RULE.parse().unwrap(), false);
+
+        assert!(!area.tick());
+    }
+
+    #[test]
+    fn day_11_simulation_settles_on_fixed_point() {
+        let grid: Grid = Grid::new(
+            10,
+            10,
+            "L.LL.LL.LLLLLLLLL.LLL.L.L..L..LLLL.LL.LLL.LL.LL.LLL.LLLLL.LL..L.L.....LLLLLLLLLLL.LLLLLL.LL.LLLLL.LL".chars().collect()).unwrap();
+        let mut area = WaitingArea::new(grid, ADJACENT_RULE.parse().unwrap(), false);
+
+        assert_eq!(Outcome::FixedPoint { step: 5 }, simulate(&mut area, 100));
+        assert_eq!(37, count_occupied_seats(&area.grid));
     }
 
     #[test]
@@ -182,7 +261,8 @@ mod test {
                 .collect(),
         )
         .unwrap();
+        let area = WaitingArea::new(grid, VISIBLE_RULE.parse().unwrap(), true);
 
-        assert_eq!(7, count_nearest_visible_occupied_neighbors(&grid, 4, 3));
+        assert_eq!(7, area.automaton.active_neighbors(&[4, 3]));
     }
 }
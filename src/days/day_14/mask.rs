@@ -0,0 +1,261 @@
+use std::iter;
+use std::str::FromStr;
+
+/// The widest word a mask can describe.
+pub const MAX_WIDTH: u32 = 64;
+
+/// A bitmask, split into bitfields so it can be applied without any string handling. Each field
+/// holds the bits of the positions in the mask with a given meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mask {
+    /// The number of positions in the mask.
+    width: u32,
+    /// The positions that aren't `0`.
+    and: u64,
+    /// The positions that are `1`.
+    or: u64,
+    /// The positions that are `X`.
+    floating: u64,
+}
+
+impl Mask {
+    /// Returns a mask of the given width that's `X` in every position.
+    pub fn all_floating(width: u32) -> Self {
+        let word = u64::MAX >> (MAX_WIDTH - width);
+        Self {
+            width,
+            and: word,
+            or: 0,
+            floating: word,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Overwrites the bits of the value wherever the mask is `0` or `1`.
+    fn apply_to_value(&self, value: u64) -> u64 {
+        value & self.and | self.or
+    }
+
+    /// Decodes an address into the set of addresses it refers to. Bits are set wherever the mask
+    /// is `1`, and float wherever it's `X`.
+    fn decode_address(&self, address: u64) -> Pattern {
+        Pattern {
+            bits: (address | self.or) & !self.floating,
+            floating: self.floating,
+        }
+    }
+}
+
+impl FromStr for Mask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.len() > MAX_WIDTH as usize {
+            return Err(format!(
+                "Mask must be between 1 and {} bits long: {}",
+                MAX_WIDTH, s
+            ));
+        }
+
+        let mut mask = Self {
+            width: s.len() as u32,
+            and: 0,
+            or: 0,
+            floating: 0,
+        };
+        for c in s.chars() {
+            mask.and <<= 1;
+            mask.or <<= 1;
+            mask.floating <<= 1;
+            match c {
+                '0' => {}
+                '1' => {
+                    mask.and |= 1;
+                    mask.or |= 1;
+                }
+                'X' => {
+                    mask.and |= 1;
+                    mask.floating |= 1;
+                }
+                _ => return Err(format!("Invalid mask: {}", s)),
+            }
+        }
+
+        Ok(mask)
+    }
+}
+
+/// How the decoder applies the mask to each write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Version 1 of the decoder chip overwrites bits of the value and leaves the address alone.
+    MaskValues,
+    /// Version 2 of the decoder chip overwrites and floats bits of the address and leaves the
+    /// value alone.
+    MaskAddresses,
+}
+
+impl Strategy {
+    /// Returns the addresses a write goes to and the value it writes.
+    pub fn decode(self, mask: &Mask, address: u64, value: u64) -> (Pattern, u64) {
+        match self {
+            Self::MaskValues => (Pattern::single(address), mask.apply_to_value(value)),
+            Self::MaskAddresses => (mask.decode_address(address), value),
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "v1" => Ok(Self::MaskValues),
+            "v2" => Ok(Self::MaskAddresses),
+            _ => Err(format!("Unrecognized decoder version: {}", s)),
+        }
+    }
+}
+
+/// A set of addresses, written as a ternary pattern: every bit in `floating` can take on either
+/// value, and the rest are given by `bits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    pub bits: u64,
+    pub floating: u64,
+}
+
+impl Pattern {
+    /// Returns a pattern that matches just the given address.
+    pub fn single(address: u64) -> Self {
+        Self {
+            bits: address,
+            floating: 0,
+        }
+    }
+
+    /// Returns the number of addresses in the set.
+    pub fn len(&self) -> u128 {
+        1 << self.floating.count_ones()
+    }
+
+    /// Lists every address in the set by iterating over the subsets of `floating`.
+    pub fn addresses(&self) -> impl Iterator<Item = u64> {
+        let Self { bits, floating } = *self;
+        let mut subset = Some(0u64);
+
+        iter::from_fn(move || {
+            let current = subset?;
+            let next = current.wrapping_sub(floating) & floating;
+            subset = if next == 0 { None } else { Some(next) };
+            Some(bits | current)
+        })
+    }
+
+    /// Returns whether any address is in both sets. They share an address unless they disagree
+    /// on a bit neither of them floats.
+    fn overlaps(&self, other: &Self) -> bool {
+        (self.bits ^ other.bits) & !self.floating & !other.floating == 0
+    }
+
+    /// Splits the addresses in this set but not in `other` into disjoint patterns. Each bit this
+    /// pattern floats but `other` doesn't splits off the addresses that disagree with `other` on
+    /// that bit, until what's left lies entirely inside `other`.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut pieces = vec![];
+        let mut rest = *self;
+        let mut split = self.floating & !other.floating;
+        while split != 0 {
+            let bit = split & split.wrapping_neg();
+            split &= !bit;
+            rest.floating &= !bit;
+            pieces.push(Self {
+                bits: rest.bits | (!other.bits & bit),
+                floating: rest.floating,
+            });
+            rest.bits |= other.bits & bit;
+        }
+
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_14_parses_mask_into_bitfields() -> Result<(), String> {
+        let mask: Mask = "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".parse()?;
+
+        assert_eq!(36, mask.width);
+        assert_eq!((1 << 36) - 1 - 0b10, mask.and);
+        assert_eq!(0b100_0000, mask.or);
+        assert_eq!((1 << 36) - 1 - 0b100_0010, mask.floating);
+        assert!("X1Y0".parse::<Mask>().is_err());
+        assert!("".parse::<Mask>().is_err());
+        assert!("X".repeat(65).parse::<Mask>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_masks_full_width_words() -> Result<(), String> {
+        let mask: Mask = format!("1{}0", "X".repeat(62)).parse()?;
+
+        assert_eq!(Mask::all_floating(64).floating, u64::MAX);
+        assert_eq!(
+            (Pattern::single(7), (1 << 63) | 6),
+            Strategy::MaskValues.decode(&mask, 7, 7)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_enumerates_floating_addresses() -> Result<(), String> {
+        let mask: Mask = "000000000000000000000000000000X1001X".parse()?;
+
+        let (addresses, value) = Strategy::MaskAddresses.decode(&mask, 42, 100);
+
+        assert_eq!(
+            vec![26, 27, 58, 59],
+            addresses.addresses().collect::<Vec<_>>()
+        );
+        assert_eq!(100, value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_subtracts_patterns_into_disjoint_pieces() {
+        let a = Pattern {
+            bits: 0b0001,
+            floating: 0b1110,
+        };
+        let b = Pattern {
+            bits: 0b0100,
+            floating: 0b0011,
+        };
+
+        let pieces = a.subtract(&b);
+
+        let mut remaining: Vec<u64> = pieces.iter().flat_map(|p| p.addresses()).collect();
+        remaining.sort_unstable();
+        let mut expected: Vec<u64> = a.addresses().filter(|x| x & 0b1100 != 0b0100).collect();
+        expected.sort_unstable();
+        assert_eq!(expected, remaining);
+        assert_eq!(
+            remaining.len() as u128,
+            pieces.iter().map(Pattern::len).sum()
+        );
+        assert_eq!(vec![a], a.subtract(&Pattern::single(0b0000)));
+    }
+}
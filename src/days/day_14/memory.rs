@@ -0,0 +1,202 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::iter;
+
+use super::mask::Pattern;
+
+/// Where the decoder stores values.
+pub trait Memory {
+    /// Writes the value to every address in the set.
+    fn store(&mut self, addresses: Pattern, value: u64);
+
+    /// Returns the sum of every value in memory. There are at most 2^64 addresses, each holding
+    /// less than 2^64, so the sum always fits.
+    fn sum(&self) -> u128;
+
+    /// Lists every address holding a value other than zero, in order, along with its value.
+    fn non_zero(&self) -> Box<dyn Iterator<Item = (u64, u64)> + '_>;
+}
+
+/// Stores every address separately, so writing to a set of addresses takes time proportional to
+/// its size.
+impl Memory for HashMap<u64, u64> {
+    fn store(&mut self, addresses: Pattern, value: u64) {
+        for address in addresses.addresses() {
+            self.insert(address, value);
+        }
+    }
+
+    fn sum(&self) -> u128 {
+        self.values().map(|value| *value as u128).sum()
+    }
+
+    fn non_zero(&self) -> Box<dyn Iterator<Item = (u64, u64)> + '_> {
+        let mut result: Vec<(u64, u64)> = self
+            .iter()
+            .filter(|(_, value)| **value != 0)
+            .map(|(address, value)| (*address, *value))
+            .collect();
+        result.sort_unstable();
+        Box::new(result.into_iter())
+    }
+}
+
+/// Stores each write as a set of addresses and a value, so writing to a set of addresses takes
+/// time proportional to the number of earlier writes it overlaps, however many addresses it
+/// covers. The sets are kept disjoint by removing each new write's addresses from the earlier
+/// ones.
+#[derive(Debug, Default)]
+pub struct PatternMemory {
+    writes: Vec<(Pattern, u64)>,
+}
+
+impl Memory for PatternMemory {
+    fn store(&mut self, addresses: Pattern, value: u64) {
+        self.writes = self
+            .writes
+            .iter()
+            .flat_map(|(pattern, old)| {
+                pattern
+                    .subtract(&addresses)
+                    .into_iter()
+                    .map(move |piece| (piece, *old))
+            })
+            .collect();
+
+        if value != 0 {
+            self.writes.push((addresses, value));
+        }
+    }
+
+    fn sum(&self) -> u128 {
+        self.writes
+            .iter()
+            .map(|(pattern, value)| pattern.len() * *value as u128)
+            .sum()
+    }
+
+    /// Merges the addresses of the writes, which each list theirs in order, so only one address
+    /// per write is held at a time, however many addresses there are.
+    fn non_zero(&self) -> Box<dyn Iterator<Item = (u64, u64)> + '_> {
+        let mut sources: Vec<_> = self
+            .writes
+            .iter()
+            .map(|(pattern, value)| (pattern.addresses(), *value))
+            .collect();
+        let mut next: BinaryHeap<Reverse<(u64, usize)>> = sources
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, (addresses, _))| addresses.next().map(|a| Reverse((a, i))))
+            .collect();
+
+        Box::new(iter::from_fn(move || {
+            let Reverse((address, i)) = next.pop()?;
+            let (addresses, value) = &mut sources[i];
+            if let Some(following) = addresses.next() {
+                next.push(Reverse((following, i)));
+            }
+            Some((address, *value))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write_overlapping(memory: &mut dyn Memory) {
+        memory.store(
+            Pattern {
+                bits: 0b1000,
+                floating: 0b0011,
+            },
+            5,
+        );
+        memory.store(
+            Pattern {
+                bits: 0b0001,
+                floating: 0b1010,
+            },
+            2,
+        );
+        memory.store(Pattern::single(0b1010), 0);
+    }
+
+    #[test]
+    fn day_14_lists_non_zero_addresses_in_order() {
+        let mut expanded: HashMap<u64, u64> = HashMap::new();
+        let mut patterns = PatternMemory::default();
+
+        write_overlapping(&mut expanded);
+        write_overlapping(&mut patterns);
+
+        let expected = vec![
+            (0b0001, 2),
+            (0b0011, 2),
+            (0b1000, 5),
+            (0b1001, 2),
+            (0b1011, 2),
+        ];
+        assert_eq!(expected, expanded.non_zero().collect::<Vec<_>>());
+        assert_eq!(expected, patterns.non_zero().collect::<Vec<_>>());
+        assert_eq!(13, expanded.sum());
+        assert_eq!(13, patterns.sum());
+    }
+
+    #[test]
+    fn day_14_pattern_memory_sums_without_expanding() {
+        let mut memory = PatternMemory::default();
+
+        memory.store(
+            Pattern {
+                bits: 0,
+                floating: u64::MAX >> 4,
+            },
+            3,
+        );
+        memory.store(
+            Pattern {
+                bits: 1,
+                floating: u64::MAX >> 4 & !1,
+            },
+            1,
+        );
+
+        assert_eq!(3 * (1 << 60) - 2 * (1 << 59), memory.sum());
+        assert_eq!(2, memory.writes.len());
+    }
+
+    #[test]
+    fn day_14_pattern_memory_sums_full_width_words() {
+        let mut memory = PatternMemory::default();
+
+        memory.store(
+            Pattern {
+                bits: 0,
+                floating: u64::MAX,
+            },
+            u64::MAX,
+        );
+
+        assert_eq!((1 << 64) * (u64::MAX as u128), memory.sum());
+    }
+
+    #[test]
+    fn day_14_pattern_memory_lists_addresses_lazily() {
+        let mut memory = PatternMemory::default();
+
+        memory.store(
+            Pattern {
+                bits: 0,
+                floating: !0b10,
+            },
+            3,
+        );
+        memory.store(Pattern::single(0b10), 7);
+
+        assert_eq!(
+            vec![(0, 3), (1, 3), (2, 7), (4, 3)],
+            memory.non_zero().take(4).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,371 @@
+mod mask;
+mod memory;
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{Error as IoError, Write};
+use std::str::FromStr;
+
+use self::mask::{Mask, Strategy, MAX_WIDTH};
+use self::memory::{Memory, PatternMemory};
+
+lazy_static::lazy_static! {
+    static ref PARSE_MEM_REGEX: Regex =
+        Regex::new(r"mem\[(?P<address>\d+)\] *= *(?P<value>\d+)").unwrap();
+}
+
+/// The number of bits in a word of the puzzle's memory.
+const WORD_WIDTH: u32 = 36;
+
+pub fn part_one(data: &[&str]) {
+    let mut decoder = Decoder::new();
+
+    if let Err(e) = decoder.load(data) {
+        println!("An error occurred while loading instructions: {}", e);
+        return;
+    }
+
+    decoder.run(Strategy::MaskValues);
+
+    println!("Sum: {}", decoder.calculate_sum());
+}
+
+pub fn part_two(data: &[&str]) {
+    let mut decoder = Decoder::with_memory(PatternMemory::default(), WORD_WIDTH).unwrap();
+
+    if let Err(e) = decoder.load(data) {
+        println!("An error occurred while loading instructions: {}", e);
+        return;
+    }
+
+    decoder.run(Strategy::MaskAddresses);
+
+    println!("Sum: {}", decoder.calculate_sum());
+}
+
+/// Runs the program on a decoder with the given version and word width, and lists every address
+/// in memory that doesn't hold zero.
+pub fn dump_memory(
+    data: &[&str],
+    version: &str,
+    width: u32,
+    out: &mut dyn Write,
+) -> Result<(), IoError> {
+    let decoder = version.parse().and_then(|strategy| {
+        let mut decoder = Decoder::with_memory(PatternMemory::default(), width)?;
+        decoder.load(data)?;
+        decoder.run(strategy);
+        Ok(decoder)
+    });
+
+    match decoder {
+        Ok(decoder) => {
+            for (address, value) in decoder.memory.non_zero() {
+                writeln!(out, "mem[{}] = {}", address, value)?;
+            }
+            Ok(())
+        }
+        Err(e) => writeln!(out, "An error occurred while loading instructions: {}", e),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Instruction {
+    Mask(Mask),
+    Mem(u64, u64),
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(mask) = s.strip_prefix("mask") {
+            return match mask.split_once('=') {
+                Some((_, mask)) => Ok(Instruction::Mask(mask.trim().parse()?)),
+                None => Err(format!("Invalid mask: {}", s)),
+            };
+        } else if s.starts_with("mem") {
+            if let Some(caps) = PARSE_MEM_REGEX.captures(s) {
+                let address = &caps["address"].parse().ok();
+                let value = &caps["value"].parse().ok();
+
+                if address.is_none() {
+                    return Err(format!("Invalid address: {}", &caps["address"]));
+                }
+
+                if value.is_none() {
+                    return Err(format!("Invalid value: {}", &caps["value"]));
+                }
+
+                return Ok(Instruction::Mem(address.unwrap(), value.unwrap()));
+            }
+        }
+
+        Err(format!("Unrecognized instruction: {}", s))
+    }
+}
+
+struct Decoder<M: Memory = HashMap<u64, u64>> {
+    memory: M,
+    instructions: Vec<Instruction>,
+    mask: Mask,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        Self::with_memory(HashMap::new(), WORD_WIDTH).unwrap()
+    }
+}
+
+impl<M: Memory> Decoder<M> {
+    /// Creates a new `Decoder` whose words have the given number of bits.
+    fn with_memory(memory: M, width: u32) -> Result<Self, String> {
+        if width == 0 || width > MAX_WIDTH {
+            return Err(format!("Word width must be between 1 and {}", MAX_WIDTH));
+        }
+
+        Ok(Self {
+            memory,
+            instructions: vec![],
+            mask: Mask::all_floating(width),
+        })
+    }
+
+    /// Loads the instructions, checking that every mask, address and value fits in a word.
+    fn load(&mut self, instructions: &[&str]) -> Result<(), String> {
+        let width = self.mask.width();
+        let fits = |n: u64| width == MAX_WIDTH || n >> width == 0;
+
+        for s in instructions {
+            let instruction = s.parse()?;
+            match instruction {
+                Instruction::Mask(mask) if mask.width() != width => {
+                    return Err(format!("Mask isn't {} bits wide: {}", width, s))
+                }
+                Instruction::Mem(address, _) if !fits(address) => {
+                    return Err(format!("Address doesn't fit in {} bits: {}", width, s))
+                }
+                Instruction::Mem(_, value) if !fits(value) => {
+                    return Err(format!("Value doesn't fit in {} bits: {}", width, s))
+                }
+                _ => {}
+            }
+            self.instructions.push(instruction);
+        }
+
+        Ok(())
+    }
+
+    /// Runs the program, applying masks the way the given version of the decoder chip does.
+    fn run(&mut self, strategy: Strategy) {
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Mask(mask) => self.mask = *mask,
+                Instruction::Mem(address, value) => {
+                    let (addresses, value) = strategy.decode(&self.mask, *address, *value);
+                    self.memory.store(addresses, value);
+                }
+            }
+        }
+    }
+
+    fn calculate_sum(&self) -> u128 {
+        self.memory.sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_14_parses_instructions() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
+            "mem[8] = 11",
+            "mem[7] = 101",
+            "mem[8] = 0",
+        ];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+
+        assert_eq!(
+            Instruction::Mask("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".parse()?),
+            decoder.instructions[0]
+        );
+        assert_eq!(Instruction::Mem(8, 11), decoder.instructions[1]);
+        assert_eq!(Instruction::Mem(7, 101), decoder.instructions[2]);
+        assert_eq!(Instruction::Mem(8, 0), decoder.instructions[3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_executes_mask_instruction() -> Result<(), String> {
+        let raw_instructions = vec!["mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskValues);
+
+        assert_eq!(
+            "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".parse::<Mask>()?,
+            decoder.mask
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_executes_mem_instruction() -> Result<(), String> {
+        let raw_instructions = vec!["mem[8] = 11"];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskValues);
+
+        assert_eq!(11, decoder.memory[&8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_executes_mem_instruction_with_applied_mask() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
+            "mem[8] = 11",
+            "mem[7] = 101",
+        ];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskValues);
+
+        assert_eq!(73, decoder.memory[&8]);
+        assert_eq!(101, decoder.memory[&7]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_calculates_sum_of_memory_values() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X",
+            "mem[8] = 11",
+            "mem[7] = 101",
+            "mem[8] = 0",
+        ];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskValues);
+
+        assert_eq!(165, decoder.calculate_sum());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_calculates_sum_of_memory_values_v2() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = 000000000000000000000000000000X1001X",
+            "mem[42] = 100",
+            "mask = 00000000000000000000000000000000X0XX",
+            "mem[26] = 1",
+        ];
+        let mut decoder = Decoder::new();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskAddresses);
+
+        assert_eq!(208, decoder.calculate_sum());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_pattern_memory_matches_expansion() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = 000000000000000000000000000000X1001X",
+            "mem[42] = 100",
+            "mask = 00000000000000000000000000000000X0XX",
+            "mem[26] = 1",
+            "mask = 0000000000000000000000000000000XX1X0",
+            "mem[3] = 7",
+            "mask = 00000000000000000000000000000000000X",
+            "mem[58] = 0",
+            "mask = 000000000000000000000000000000XXXXXX",
+            "mem[0] = 2",
+            "mask = 00000000000000000000000000000000X0X1",
+            "mem[8] = 5",
+        ];
+
+        for end in (2..=raw_instructions.len()).step_by(2) {
+            let mut expanded = Decoder::new();
+            let mut patterns = Decoder::with_memory(PatternMemory::default(), WORD_WIDTH).unwrap();
+            expanded.load(&raw_instructions[..end])?;
+            patterns.load(&raw_instructions[..end])?;
+
+            expanded.run(Strategy::MaskAddresses);
+            patterns.run(Strategy::MaskAddresses);
+
+            assert_eq!(expanded.calculate_sum(), patterns.calculate_sum());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_pattern_memory_sums_without_expanding() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX",
+            "mem[0] = 3",
+            "mask = 0XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX1",
+            "mem[0] = 1",
+        ];
+        let mut decoder = Decoder::with_memory(PatternMemory::default(), WORD_WIDTH).unwrap();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskAddresses);
+
+        assert_eq!(3 * (1 << 36) - 2 * (1 << 34), decoder.calculate_sum());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_sums_values_beyond_u64() -> Result<(), String> {
+        let raw_instructions = vec![
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX",
+            "mem[0] = 68719476735",
+        ];
+        let mut decoder = Decoder::with_memory(PatternMemory::default(), WORD_WIDTH).unwrap();
+
+        decoder.load(&raw_instructions)?;
+        decoder.run(Strategy::MaskAddresses);
+
+        assert_eq!((1 << 36) * ((1 << 36) - 1), decoder.calculate_sum());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_14_checks_instructions_fit_word_width() -> Result<(), String> {
+        let mut decoder = Decoder::with_memory(PatternMemory::default(), 4)?;
+
+        decoder.load(&["mask = 1X0X", "mem[15] = 15"])?;
+        decoder.run(Strategy::MaskAddresses);
+
+        assert_eq!(
+            vec![(10, 15), (11, 15), (14, 15), (15, 15)],
+            decoder.memory.non_zero().collect::<Vec<_>>()
+        );
+        assert!(decoder.load(&["mask = 1X0"]).is_err());
+        assert!(decoder.load(&["mem[16] = 1"]).is_err());
+        assert!(decoder.load(&["mem[1] = 16"]).is_err());
+        assert!(Decoder::with_memory(PatternMemory::default(), 65).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// A set of numbers made of inclusive intervals, kept sorted with any that overlap or touch
+/// merged, so membership is a binary search.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet {
+    intervals: Vec<(u32, u32)>,
+}
+
+impl IntervalSet {
+    /// Builds the set holding every number in any of the intervals, given as `(low, high)`
+    /// pairs in any order. Intervals with `low` above `high` are empty.
+    pub fn new<I>(intervals: I) -> Self
+    where
+        I: IntoIterator<Item = (u32, u32)>,
+    {
+        let mut sorted: Vec<(u32, u32)> = intervals
+            .into_iter()
+            .filter(|(low, high)| low <= high)
+            .collect();
+        sorted.sort_unstable();
+
+        let mut merged: Vec<(u32, u32)> = vec![];
+        for (low, high) in sorted {
+            match merged.last_mut() {
+                Some(last) if low <= last.1.saturating_add(1) => last.1 = last.1.max(high),
+                _ => merged.push((low, high)),
+            }
+        }
+
+        Self { intervals: merged }
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        let i = self.intervals.partition_point(|(_, high)| *high < value);
+        self.intervals.get(i).is_some_and(|(low, _)| *low <= value)
+    }
+
+    /// Returns how far the value is from the nearest number in the set, which is zero for
+    /// numbers in it, or `None` if the set is empty.
+    pub fn distance(&self, value: u32) -> Option<u32> {
+        let i = self.intervals.partition_point(|(_, high)| *high < value);
+        let above = self
+            .intervals
+            .get(i)
+            .map(|(low, _)| low.saturating_sub(value));
+        let below = i.checked_sub(1).map(|j| value - self.intervals[j].1);
+
+        match (below, above) {
+            (Some(below), Some(above)) => Some(below.min(above)),
+            (below, above) => below.or(above),
+        }
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let intervals: Vec<String> = self
+            .intervals
+            .iter()
+            .map(|(low, high)| format!("{}-{}", low, high))
+            .collect();
+        write!(f, "{}", intervals.join(" or "))
+    }
+}
+
+impl FromStr for IntervalSet {
+    type Err = String;
+
+    /// Parses intervals like `1-3 or 5-7 or 9-9`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_bound = |bound: &str| {
+            bound
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid interval bound: {}", bound.trim()))
+        };
+
+        let intervals = s
+            .split(" or ")
+            .map(|interval| {
+                let (low, high) = match interval.split_once('-') {
+                    Some(bounds) => bounds,
+                    None => return Err(format!("Invalid interval: {}", interval.trim())),
+                };
+                let (low, high) = (parse_bound(low)?, parse_bound(high)?);
+                if low > high {
+                    return Err(format!("Interval runs backwards: {}", interval.trim()));
+                }
+                Ok((low, high))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self::new(intervals))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_16_merges_intervals() {
+        let set = IntervalSet::new(vec![(10, 12), (1, 3), (4, 5), (2, 2), (20, 30), (25, 40)]);
+
+        assert_eq!("1-5 or 10-12 or 20-40", set.to_string());
+        assert!(set.contains(1));
+        assert!(set.contains(5));
+        assert!(!set.contains(6));
+        assert!(!set.contains(9));
+        assert!(set.contains(40));
+        assert!(!set.contains(41));
+        assert!(!set.contains(0));
+    }
+
+    #[test]
+    fn day_16_measures_distance_to_intervals() {
+        let set = IntervalSet::new(vec![(5, 7), (20, 30)]);
+
+        assert_eq!(Some(5), set.distance(0));
+        assert_eq!(Some(0), set.distance(6));
+        assert_eq!(Some(1), set.distance(8));
+        assert_eq!(Some(2), set.distance(18));
+        assert_eq!(Some(70), set.distance(100));
+        assert_eq!(None, IntervalSet::default().distance(3));
+    }
+
+    #[test]
+    fn day_16_parses_intervals() {
+        assert_eq!(
+            Ok(IntervalSet::new(vec![(1, 3), (5, 7), (9, 9)])),
+            "1-3 or 5-7 or 9-9".parse()
+        );
+        assert_eq!(
+            Err(String::from("Interval runs backwards: 7-5")),
+            "1-3 or 7-5".parse::<IntervalSet>()
+        );
+        assert_eq!(
+            Err(String::from("Invalid interval bound: x")),
+            "1-x".parse::<IntervalSet>()
+        );
+        assert_eq!(
+            Err(String::from("Invalid interval: 4")),
+            "1-3 or 4".parse::<IntervalSet>()
+        );
+    }
+}
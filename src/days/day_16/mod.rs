@@ -0,0 +1,659 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::str::FromStr;
+
+mod intervals;
+mod solver;
+
+use self::intervals::IntervalSet;
+
+/// The prefix of the fields part two multiplies together.
+const DEFAULT_PREFIX: &str = "departure";
+
+pub fn part_one(data: &[&str]) {
+    let (rules, _, nearby_tickets) = match parse_input(data) {
+        Ok(input) => input,
+        Err(e) => return println!("{}", e),
+    };
+    let sum: u32 = find_invalid_values(&rules, &nearby_tickets)
+        .iter()
+        .map(|invalid| invalid.value)
+        .sum();
+
+    println!("Ticket scanning error rate: {}", sum);
+}
+
+pub fn part_two(data: &[&str]) {
+    let (rules, your_ticket, nearby_tickets) = match parse_input(data) {
+        Ok(input) => input,
+        Err(e) => return println!("{}", e),
+    };
+    let fields = match determine_fields(&rules, &your_ticket, &nearby_tickets) {
+        Ok(fields) => fields,
+        Err(e) => return println!("{}", e),
+    };
+
+    match field_product(&fields, DEFAULT_PREFIX) {
+        Ok(product) => println!("Product: {}", product),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Writes every invalid value on the nearby tickets along with the rule it comes closest to
+/// satisfying, then the product of your ticket's fields whose names start with the prefix.
+pub fn validate_tickets(data: &[&str], prefix: &str, out: &mut dyn Write) -> Result<(), IoError> {
+    let (rules, your_ticket, nearby_tickets) = parse_ticket_data(data)?;
+
+    let invalid_values = find_invalid_values(&rules, &nearby_tickets);
+    for invalid in &invalid_values {
+        write!(
+            out,
+            "Nearby ticket {}, column {}: {} is invalid",
+            invalid.ticket + 1,
+            invalid.column + 1,
+            invalid.value
+        )?;
+        match invalid.closest {
+            Some((rule, distance)) => writeln!(
+                out,
+                "; closest is {} ({}), off by {}",
+                rules[rule].field, rules[rule].ranges, distance
+            )?,
+            None => writeln!(out, "; there are no rules")?,
+        }
+    }
+
+    let mut invalid_tickets: Vec<usize> = invalid_values.iter().map(|i| i.ticket).collect();
+    invalid_tickets.dedup();
+    writeln!(
+        out,
+        "{} of {} nearby tickets are invalid; the scanning error rate is {}",
+        invalid_tickets.len(),
+        nearby_tickets.len(),
+        invalid_values.iter().map(|i| i.value as u64).sum::<u64>()
+    )?;
+
+    match determine_fields(&rules, &your_ticket, &nearby_tickets) {
+        Ok(fields) => match field_product(&fields, prefix) {
+            Ok(product) => writeln!(
+                out,
+                "Product of fields starting with \"{}\": {}",
+                prefix, product
+            ),
+            Err(e) => writeln!(out, "{}", e),
+        },
+        Err(e) => writeln!(out, "{}", e),
+    }
+}
+
+/// Writes your ticket and the valid nearby tickets as CSV, with a column for each field in the
+/// order the tickets list them, headed by the field's name.
+pub fn export_tickets(data: &[&str], out: &mut dyn Write) -> Result<(), IoError> {
+    let (rules, your_ticket, nearby_tickets) = parse_ticket_data(data)?;
+    let names = find_field_order(&rules, &your_ticket, &nearby_tickets)
+        .map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+
+    let header: Vec<String> = names.iter().map(|name| csv_field(name)).collect();
+    writeln!(out, "ticket,{}", header.join(","))?;
+
+    let valid_tickets = nearby_tickets
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| is_valid_ticket(t, &rules))
+        .map(|(i, t)| ((i + 1).to_string(), t));
+    for (ticket, values) in Some((String::from("yours"), &your_ticket))
+        .into_iter()
+        .chain(valid_tickets)
+    {
+        let values: Vec<String> = values.iter().map(u32::to_string).collect();
+        writeln!(out, "{},{}", ticket, values.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Writes up to `limit` of the ways the fields can be given to the columns of the tickets, each
+/// as the field names in column order, saying so if there are more.
+pub fn list_assignments(data: &[&str], limit: usize, out: &mut dyn Write) -> Result<(), IoError> {
+    let (rules, your_ticket, nearby_tickets) = parse_ticket_data(data)?;
+    let candidates = find_candidates(&rules, your_ticket.len(), &nearby_tickets);
+    let names: Vec<&str> = rules.iter().map(|r| &r.field[..]).collect();
+
+    let assignments = match solver::assignments(&candidates, limit.saturating_add(1)) {
+        Ok(assignments) => assignments,
+        Err(e) => return writeln!(out, "{}", e.describe(&names)),
+    };
+    for (i, assignment) in assignments.iter().take(limit).enumerate() {
+        let fields: Vec<&str> = assignment.iter().map(|rule| names[*rule]).collect();
+        writeln!(out, "Assignment {}: {}", i + 1, fields.join(", "))?;
+    }
+    if assignments.len() > limit {
+        writeln!(out, "Stopped after {} assignments", limit)?;
+    }
+
+    Ok(())
+}
+
+/// Parses the input for the ticket subcommands, which all fail the same way on input they can't
+/// read.
+fn parse_ticket_data(data: &[&str]) -> Result<Input, IoError> {
+    parse_input(data).map_err(|e| IoError::new(ErrorKind::InvalidData, e))
+}
+
+/// Quotes a CSV field if it holds a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+struct Rule {
+    field: String,
+    ranges: IntervalSet,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.ranges)
+    }
+}
+
+impl Rule {
+    fn is_valid(&self, value: u32) -> bool {
+        self.ranges.contains(value)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(": ") {
+            Some((field, ranges)) if !field.is_empty() => Ok(Self {
+                field: field.to_string(),
+                ranges: ranges.parse()?,
+            }),
+            _ => Err(format!("Invalid rule: {}", s)),
+        }
+    }
+}
+
+/// The rules, your ticket and the nearby tickets.
+type Input = (Vec<Rule>, Vec<u32>, Vec<Vec<u32>>);
+
+/// The part of the input a line belongs to.
+enum Section {
+    Rules,
+    YourTicket,
+    NearbyTickets,
+}
+
+fn parse_ticket(line: &str) -> Result<Vec<u32>, String> {
+    line.split(',')
+        .map(|s| {
+            s.trim()
+                .parse()
+                .map_err(|_| format!("Invalid ticket value: {}", s.trim()))
+        })
+        .collect()
+}
+
+/// Parses the rules, your ticket and the nearby tickets, failing on the first line that doesn't
+/// fit the section it's in, or on a nearby ticket with a different number of values from yours.
+/// The sections can come in any order, so the nearby tickets are checked against yours once
+/// every line is read.
+fn parse_input(data: &[&str]) -> Result<Input, String> {
+    let mut rules = vec![];
+    let mut your_ticket = None;
+    let mut nearby_tickets = vec![];
+
+    let mut section = Section::Rules;
+
+    for (i, line) in data.iter().enumerate() {
+        let fail = |e: String| format!("Line {}: {}", i + 1, e);
+
+        match line.trim() {
+            "" => continue,
+            "your ticket:" => section = Section::YourTicket,
+            "nearby tickets:" => section = Section::NearbyTickets,
+            line => match section {
+                Section::Rules => rules.push(line.parse().map_err(fail)?),
+                Section::YourTicket if your_ticket.is_some() => {
+                    return Err(fail(String::from("There is more than one ticket of yours")))
+                }
+                Section::YourTicket => your_ticket = Some(parse_ticket(line).map_err(fail)?),
+                Section::NearbyTickets => {
+                    nearby_tickets.push((i, parse_ticket(line).map_err(fail)?))
+                }
+            },
+        }
+    }
+
+    let your_ticket =
+        your_ticket.ok_or_else(|| String::from("The input has no ticket of yours"))?;
+    for (i, ticket) in &nearby_tickets {
+        if ticket.len() != your_ticket.len() {
+            return Err(format!(
+                "Line {}: The ticket has {} values, but yours has {}",
+                i + 1,
+                ticket.len(),
+                your_ticket.len()
+            ));
+        }
+    }
+
+    let nearby_tickets = nearby_tickets
+        .into_iter()
+        .map(|(_, ticket)| ticket)
+        .collect();
+    Ok((rules, your_ticket, nearby_tickets))
+}
+
+/// A value on a nearby ticket that no rule allows.
+#[derive(Debug, PartialEq, Eq)]
+struct InvalidValue {
+    ticket: usize,
+    column: usize,
+    value: u32,
+    /// The rule the value comes closest to satisfying, and how far it is from it, if there are
+    /// any rules. Ties go to the rule listed first.
+    closest: Option<(usize, u32)>,
+}
+
+/// Finds every invalid value on the nearby tickets, in ticket and column order.
+fn find_invalid_values(rules: &[Rule], nearby_tickets: &[Vec<u32>]) -> Vec<InvalidValue> {
+    nearby_tickets
+        .iter()
+        .enumerate()
+        .flat_map(|(ticket, values)| {
+            values
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| !rules.iter().any(|r| r.is_valid(**v)))
+                .map(move |(column, value)| InvalidValue {
+                    ticket,
+                    column,
+                    value: *value,
+                    closest: rules
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, r)| r.ranges.distance(*value).map(|d| (i, d)))
+                        .min_by_key(|(_, d)| *d),
+                })
+        })
+        .collect()
+}
+
+fn is_valid_ticket(ticket: &[u32], rules: &[Rule]) -> bool {
+    ticket.iter().all(|v| rules.iter().any(|r| r.is_valid(*v)))
+}
+
+/// Lists, for each column of the tickets, the indices of the rules that every valid nearby
+/// ticket's value in that column satisfies.
+fn find_candidates(rules: &[Rule], columns: usize, nearby_tickets: &[Vec<u32>]) -> Vec<Vec<usize>> {
+    let valid_tickets: Vec<&Vec<u32>> = nearby_tickets
+        .iter()
+        .filter(|t| is_valid_ticket(t, rules))
+        .collect();
+
+    (0..columns)
+        .map(|c| {
+            (0..rules.len())
+                .filter(|r| valid_tickets.iter().all(|t| rules[*r].is_valid(t[c])))
+                .collect()
+        })
+        .collect()
+}
+
+/// Works out which field each column of the tickets holds, returning the field names in
+/// column order, or why the fields can't be told apart.
+fn find_field_order<'a>(
+    rules: &'a [Rule],
+    your_ticket: &[u32],
+    nearby_tickets: &[Vec<u32>],
+) -> Result<Vec<&'a str>, String> {
+    let candidates = find_candidates(rules, your_ticket.len(), nearby_tickets);
+    let names: Vec<&str> = rules.iter().map(|r| &r.field[..]).collect();
+
+    let assignment = solver::unique_assignment(&candidates).map_err(|e| e.describe(&names))?;
+
+    Ok(assignment.iter().map(|rule| names[*rule]).collect())
+}
+
+/// Returns your ticket's value for every field.
+fn determine_fields<'a>(
+    rules: &'a [Rule],
+    your_ticket: &[u32],
+    nearby_tickets: &[Vec<u32>],
+) -> Result<HashMap<&'a str, u32>, String> {
+    let names = find_field_order(rules, your_ticket, nearby_tickets)?;
+
+    Ok(names.into_iter().zip(your_ticket.iter().copied()).collect())
+}
+
+/// Multiplies together the values of the fields whose names start with the prefix, failing if
+/// none do or the product doesn't fit in 64 bits.
+fn field_product(fields: &HashMap<&str, u32>, prefix: &str) -> Result<u64, String> {
+    let values: Vec<u64> = fields
+        .iter()
+        .filter(|(name, _)| name.starts_with(prefix))
+        .map(|(_, value)| *value as u64)
+        .collect();
+    if values.is_empty() {
+        return Err(format!("No field starts with \"{}\"", prefix));
+    }
+
+    values
+        .iter()
+        .try_fold(1u64, |product, value| product.checked_mul(*value))
+        .ok_or_else(|| {
+            format!(
+                "The product of the fields starting with \"{}\" is too large",
+                prefix
+            )
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_16_parse_data() {
+        let data = vec![
+            "class: 1-3 or 5-7",
+            "row: 6-11 or 33-44",
+            "seat: 13-40 or 45-50",
+            "",
+            "your ticket:",
+            "7,1,14",
+            "",
+            "nearby tickets:",
+            "7,3,47",
+            "40,4,50",
+            "55,2,20",
+            "38,6,12",
+        ];
+        let (rules, your_ticket, nearby_tickets) = parse_input(&data).unwrap();
+
+        assert_eq!(
+            Rule {
+                field: "class".into(),
+                ranges: IntervalSet::new(vec![(1, 3), (5, 7)])
+            },
+            rules[0]
+        );
+        assert_eq!(
+            Rule {
+                field: "row".into(),
+                ranges: IntervalSet::new(vec![(6, 11), (33, 44)])
+            },
+            rules[1]
+        );
+        assert_eq!(
+            Rule {
+                field: "seat".into(),
+                ranges: IntervalSet::new(vec![(13, 40), (45, 50)])
+            },
+            rules[2]
+        );
+
+        assert_eq!(vec![7, 1, 14], your_ticket);
+
+        assert_eq!(vec![7, 3, 47], nearby_tickets[0]);
+        assert_eq!(vec![40, 4, 50], nearby_tickets[1]);
+        assert_eq!(vec![55, 2, 20], nearby_tickets[2]);
+        assert_eq!(vec![38, 6, 12], nearby_tickets[3]);
+    }
+
+    #[test]
+    fn day_16_identify_invalid_nearby_tickets() {
+        let data = vec![
+            "class: 1-3 or 5-7",
+            "row: 6-11 or 33-44",
+            "seat: 13-40 or 45-50",
+            "",
+            "your ticket:",
+            "7,1,14",
+            "",
+            "nearby tickets:",
+            "7,3,47",
+            "40,4,50",
+            "55,2,20",
+            "38,6,12",
+        ];
+        let (rules, _, nearby_tickets) = parse_input(&data).unwrap();
+
+        let invalid_values = find_invalid_values(&rules, &nearby_tickets);
+
+        assert_eq!(71u32, invalid_values.iter().map(|i| i.value).sum());
+        assert_eq!(
+            InvalidValue {
+                ticket: 1,
+                column: 1,
+                value: 4,
+                closest: Some((0, 1)),
+            },
+            invalid_values[0]
+        );
+    }
+
+    #[test]
+    fn day_16_report_every_invalid_value() {
+        let data = vec![
+            "class: 1-3 or 5-7",
+            "row: 6-11 or 33-44",
+            "departure seat: 13-40 or 45-50",
+            "",
+            "your ticket:",
+            "7,1,14",
+            "",
+            "nearby tickets:",
+            "7,3,47",
+            "40,4,50",
+            "55,2,20",
+            "38,6,12",
+            "0,60,13",
+        ];
+        let mut out = vec![];
+
+        validate_tickets(&data, "departure", &mut out).unwrap();
+
+        assert_eq!(
+            "Nearby ticket 2, column 2: 4 is invalid; closest is class (1-3 or 5-7), off by 1\n\
+             Nearby ticket 3, column 1: 55 is invalid; closest is departure seat (13-40 or 45-50), \
+             off by 5\n\
+             Nearby ticket 4, column 3: 12 is invalid; closest is row (6-11 or 33-44), off by 1\n\
+             Nearby ticket 5, column 1: 0 is invalid; closest is class (1-3 or 5-7), off by 1\n\
+             Nearby ticket 5, column 2: 60 is invalid; closest is departure seat (13-40 or 45-50), \
+             off by 10\n\
+             4 of 5 nearby tickets are invalid; the scanning error rate is 131\n\
+             Product of fields starting with \"departure\": 14\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_16_multiply_fields_with_prefix() {
+        let fields: HashMap<&str, u32> =
+            vec![("arrival a", 1 << 30), ("arrival b", 1 << 30), ("c", 16)]
+                .into_iter()
+                .collect();
+
+        assert_eq!(Ok(16), field_product(&fields, "c"));
+        assert_eq!(Ok(1 << 60), field_product(&fields, "arrival"));
+        assert_eq!(
+            Err(String::from(
+                "The product of the fields starting with \"\" is too large"
+            )),
+            field_product(&fields, "")
+        );
+        assert_eq!(
+            Err(String::from("No field starts with \"d\"")),
+            field_product(&fields, "d")
+        );
+    }
+
+    #[test]
+    fn day_16_export_decoded_tickets() {
+        let data = vec![
+            "class: 0-1 or 4-19",
+            "row, or not: 0-5 or 8-19",
+            "seat: 0-13 or 16-19",
+            "",
+            "your ticket:",
+            "11,12,13",
+            "",
+            "nearby tickets:",
+            "3,9,18",
+            "15,1,5",
+            "5,14,9",
+            "5,14,90",
+        ];
+        let mut out = vec![];
+
+        export_tickets(&data, &mut out).unwrap();
+
+        assert_eq!(
+            "ticket,\"row, or not\",class,seat\n\
+             yours,11,12,13\n\
+             1,3,9,18\n\
+             2,15,1,5\n\
+             3,5,14,9\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_16_determine_fields() {
+        let data = vec![
+            "class: 0-1 or 4-19",
+            "row: 0-5 or 8-19",
+            "seat: 0-13 or 16-19",
+            "",
+            "your ticket:",
+            "11,12,13",
+            "",
+            "nearby tickets:",
+            "3,9,18",
+            "15,1,5",
+            "5,14,9",
+        ];
+        let (rules, your_ticket, nearby_tickets) = parse_input(&data).unwrap();
+
+        let fields = determine_fields(&rules, &your_ticket, &nearby_tickets).unwrap();
+
+        assert_eq!(12, fields["class"]);
+        assert_eq!(11, fields["row"]);
+        assert_eq!(13, fields["seat"]);
+    }
+
+    #[test]
+    fn day_16_reports_ambiguous_fields() {
+        let data = vec![
+            "class: 0-1 or 4-19",
+            "row: 0-5 or 8-19",
+            "",
+            "your ticket:",
+            "11,12",
+            "",
+            "nearby tickets:",
+            "4,9",
+            "15,1",
+        ];
+        let (rules, your_ticket, nearby_tickets) = parse_input(&data).unwrap();
+
+        assert_eq!(
+            Err(String::from(
+                "Ambiguous: columns 1, 2 can hold more than one field"
+            )),
+            determine_fields(&rules, &your_ticket, &nearby_tickets)
+        );
+    }
+
+    #[test]
+    fn day_16_lists_every_assignment() {
+        let data = vec![
+            "class: 0-1 or 4-19",
+            "row: 0-5 or 8-19",
+            "seat: 0-13 or 16-19",
+            "",
+            "your ticket:",
+            "11,12,13",
+            "",
+            "nearby tickets:",
+            "3,9,18",
+            "15,1,5",
+        ];
+        let mut out = vec![];
+
+        list_assignments(&data, 1, &mut out).unwrap();
+        list_assignments(&data, 2, &mut out).unwrap();
+        let bad_input = ["class: 0-1", "", "your ticket:", "x"];
+        for result in [
+            validate_tickets(&bad_input, "class", &mut vec![]),
+            export_tickets(&bad_input, &mut vec![]),
+            list_assignments(&bad_input, 1, &mut vec![]),
+        ] {
+            assert_eq!(ErrorKind::InvalidData, result.unwrap_err().kind());
+        }
+
+        assert_eq!(
+            "Assignment 1: row, class, seat\n\
+             Stopped after 1 assignments\n\
+             Assignment 1: row, class, seat\n\
+             Assignment 2: row, seat, class\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_16_parse_rules_with_many_intervals() {
+        let data = vec!["class: 1-3 or 5-7 or 9-9 or 4-4", "", "your ticket:", "7"];
+        let (rules, _, _) = parse_input(&data).unwrap();
+
+        assert_eq!("class: 1-7 or 9-9", rules[0].to_string());
+        assert!(!rules[0].is_valid(8));
+    }
+
+    #[test]
+    fn day_16_reject_lines_that_do_not_fit() {
+        let error = |data: &[&str]| parse_input(data).err();
+
+        assert_eq!(
+            Some(String::from("Line 2: Invalid rule: row 6-11")),
+            error(&["class: 1-3", "row 6-11", "your ticket:", "1"])
+        );
+        assert_eq!(
+            Some(String::from("Line 3: Invalid ticket value: x")),
+            error(&["class: 1-3", "your ticket:", "x"])
+        );
+        assert_eq!(
+            Some(String::from(
+                "Line 5: The ticket has 2 values, but yours has 1"
+            )),
+            error(&["class: 1-3", "your ticket:", "1", "nearby tickets:", "1,2"])
+        );
+        assert_eq!(
+            Some(String::from(
+                "Line 4: The ticket has 1 values, but yours has 2"
+            )),
+            error(&[
+                "class: 1-3",
+                "nearby tickets:",
+                "1,2",
+                "3",
+                "your ticket:",
+                "1,2"
+            ])
+        );
+        assert_eq!(
+            Some(String::from("The input has no ticket of yours")),
+            error(&["class: 1-3", "nearby tickets:", "1"])
+        );
+    }
+}
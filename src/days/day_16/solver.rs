@@ -0,0 +1,386 @@
+/// Why the columns of a ticket can't be given fields one way only.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The columns between them can only take fewer fields than there are columns. Both lists
+    /// are indices, in order.
+    Unsatisfiable {
+        columns: Vec<usize>,
+        fields: Vec<usize>,
+    },
+    /// More than one assignment fits, and the columns listed take different fields in two of
+    /// them.
+    Ambiguous { columns: Vec<usize> },
+}
+
+impl SolveError {
+    /// Describes the error, naming the fields and numbering the columns from one.
+    pub fn describe(&self, names: &[&str]) -> String {
+        let columns = |columns: &[usize]| -> String {
+            let columns: Vec<String> = columns.iter().map(|c| (c + 1).to_string()).collect();
+            columns.join(", ")
+        };
+
+        match self {
+            Self::Unsatisfiable {
+                columns: conflicting,
+                fields,
+            } => {
+                let fields: Vec<&str> = fields.iter().map(|f| names[*f]).collect();
+                format!(
+                    "Unsatisfiable: columns {} can only hold the {} fields {}",
+                    columns(conflicting),
+                    fields.len(),
+                    match fields.is_empty() {
+                        true => String::from("(none)"),
+                        false => fields.join(", "),
+                    }
+                )
+            }
+            Self::Ambiguous {
+                columns: conflicting,
+            } => format!(
+                "Ambiguous: columns {} can hold more than one field",
+                columns(conflicting)
+            ),
+        }
+    }
+}
+
+/// Gives each column its own field, where `candidates[column]` lists the fields that every value
+/// in the column is valid for. Returns up to `limit` of the assignments that fit, each listing
+/// the field of every column, or the columns that can't all be given a field.
+pub fn assignments(candidates: &[Vec<usize>], limit: usize) -> Result<Vec<Vec<usize>>, SolveError> {
+    find_matching(candidates)?;
+
+    let fields = candidates.iter().flatten().max().map_or(0, |f| f + 1);
+    let mut search = Search {
+        candidates,
+        assigned: vec![None; candidates.len()],
+        used: vec![false; fields],
+        results: vec![],
+        limit,
+    };
+    search.run();
+
+    Ok(search.results)
+}
+
+/// Gives each column its own field, failing unless exactly one assignment fits. When more than
+/// one does, every column whose field isn't forced is reported.
+pub fn unique_assignment(candidates: &[Vec<usize>]) -> Result<Vec<usize>, SolveError> {
+    let matching = find_matching(candidates)?;
+    let columns = find_unforced(candidates, &matching);
+    if !columns.is_empty() {
+        return Err(SolveError::Ambiguous { columns });
+    }
+
+    Ok(matching)
+}
+
+/// Finds the largest matching of columns to fields with augmenting paths, returning the field of
+/// each column. If some column is left over, the columns reachable from it by alternating paths
+/// only share the fields matched to the rest of them, which is one fewer field than columns.
+fn find_matching(candidates: &[Vec<usize>]) -> Result<Vec<usize>, SolveError> {
+    let fields = candidates.iter().flatten().max().map_or(0, |f| f + 1);
+    let mut matched: Vec<Option<usize>> = vec![None; fields];
+
+    for column in 0..candidates.len() {
+        let mut visited = vec![false; fields];
+        if !augment(candidates, column, &mut matched, &mut visited) {
+            let mut columns: Vec<usize> = (0..fields)
+                .filter(|f| visited[*f])
+                .filter_map(|f| matched[f])
+                .chain(Some(column))
+                .collect();
+            columns.sort_unstable();
+            let fields = (0..fields).filter(|f| visited[*f]).collect();
+            return Err(SolveError::Unsatisfiable { columns, fields });
+        }
+    }
+
+    let mut result = vec![0; candidates.len()];
+    for (field, column) in matched.iter().enumerate() {
+        if let Some(column) = column {
+            result[*column] = field;
+        }
+    }
+    Ok(result)
+}
+
+/// Lists the columns that could take a different field in some other assignment. A column can
+/// swap its field for another candidate along an alternating cycle, where each column in turn
+/// takes the field of the next, or along an alternating path that ends at a field no column
+/// holds. Following the edge from a column to the holder of each of its other candidates, the
+/// cycles are the strongly connected components with more than one column.
+fn find_unforced(candidates: &[Vec<usize>], matching: &[usize]) -> Vec<usize> {
+    let fields = candidates.iter().flatten().max().map_or(0, |f| f + 1);
+    let mut holder = vec![None; fields];
+    for (column, field) in matching.iter().enumerate() {
+        holder[*field] = Some(column);
+    }
+
+    let mut free = vec![false; candidates.len()];
+    let edges: Vec<Vec<usize>> = candidates
+        .iter()
+        .enumerate()
+        .map(|(column, fields)| {
+            fields
+                .iter()
+                .filter(|f| **f != matching[column])
+                .filter_map(|f| {
+                    if holder[*f].is_none() {
+                        free[column] = true;
+                    }
+                    holder[*f]
+                })
+                .collect()
+        })
+        .collect();
+
+    let components = strongly_connected_components(&edges);
+    let mut size = vec![0; candidates.len()];
+    for component in &components {
+        size[*component] += 1;
+    }
+
+    // A column that can reach a free field can pass its own field down the path.
+    let mut unforced = free;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for column in 0..candidates.len() {
+            if !unforced[column] && edges[column].iter().any(|c| unforced[*c]) {
+                unforced[column] = true;
+                changed = true;
+            }
+        }
+    }
+
+    (0..candidates.len())
+        .filter(|c| unforced[*c] || size[components[*c]] > 1)
+        .collect()
+}
+
+/// Numbers the strongly connected components of a graph with Tarjan's algorithm, returning the
+/// component of each node.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    struct Tarjan<'a> {
+        edges: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        component: Vec<usize>,
+        next_index: usize,
+        next_component: usize,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next_index);
+            self.low[node] = self.next_index;
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+
+            for next in &self.edges[node] {
+                match self.index[*next] {
+                    None => {
+                        self.visit(*next);
+                        self.low[node] = self.low[node].min(self.low[*next]);
+                    }
+                    Some(index) if self.on_stack[*next] => {
+                        self.low[node] = self.low[node].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if Some(self.low[node]) == self.index[node] {
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    self.component[member] = self.next_component;
+                    if member == node {
+                        break;
+                    }
+                }
+                self.next_component += 1;
+            }
+        }
+    }
+
+    let nodes = edges.len();
+    let mut tarjan = Tarjan {
+        edges,
+        index: vec![None; nodes],
+        low: vec![0; nodes],
+        stack: vec![],
+        on_stack: vec![false; nodes],
+        component: vec![0; nodes],
+        next_index: 0,
+        next_component: 0,
+    };
+    for node in 0..nodes {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    tarjan.component
+}
+
+/// Looks for an alternating path from the column to a free field, marking every field it
+/// tries, and flips the path's edges if it finds one.
+fn augment(
+    candidates: &[Vec<usize>],
+    column: usize,
+    matched: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for field in &candidates[column] {
+        if visited[*field] {
+            continue;
+        }
+        visited[*field] = true;
+
+        let free = match matched[*field] {
+            None => true,
+            Some(other) => augment(candidates, other, matched, visited),
+        };
+        if free {
+            matched[*field] = Some(column);
+            return true;
+        }
+    }
+    false
+}
+
+/// A backtracking search for assignments, which always tries the column with the fewest
+/// fields left next, so columns with only one field left are settled straight away.
+struct Search<'a> {
+    candidates: &'a [Vec<usize>],
+    assigned: Vec<Option<usize>>,
+    used: Vec<bool>,
+    results: Vec<Vec<usize>>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    fn run(&mut self) {
+        if self.results.len() >= self.limit {
+            return;
+        }
+
+        let next = (0..self.candidates.len())
+            .filter(|c| self.assigned[*c].is_none())
+            .map(|c| (self.available(c).count(), c))
+            .min();
+        let column = match next {
+            None => {
+                self.results
+                    .push(self.assigned.iter().map(|f| f.unwrap()).collect());
+                return;
+            }
+            Some((0, _)) => return,
+            Some((_, column)) => column,
+        };
+
+        let fields: Vec<usize> = self.available(column).collect();
+        for field in fields {
+            self.assigned[column] = Some(field);
+            self.used[field] = true;
+            self.run();
+            self.used[field] = false;
+        }
+        self.assigned[column] = None;
+    }
+
+    fn available(&self, column: usize) -> impl Iterator<Item = usize> + '_ {
+        self.candidates[column]
+            .iter()
+            .copied()
+            .filter(move |f| !self.used[*f])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_16_solves_by_elimination() {
+        let candidates = vec![vec![0, 1, 2], vec![1], vec![1, 2]];
+
+        assert_eq!(Ok(vec![0, 1, 2]), unique_assignment(&candidates));
+    }
+
+    #[test]
+    fn day_16_finds_every_assignment() {
+        let candidates = vec![vec![0, 1], vec![0, 1], vec![2]];
+
+        assert_eq!(
+            Ok(vec![vec![0, 1, 2], vec![1, 0, 2]]),
+            assignments(&candidates, usize::MAX)
+        );
+        assert_eq!(
+            Err(SolveError::Ambiguous {
+                columns: vec![0, 1]
+            }),
+            unique_assignment(&candidates)
+        );
+    }
+
+    #[test]
+    fn day_16_reports_every_unforced_column() {
+        let interchangeable = vec![vec![0, 1, 2, 3, 4]; 5];
+        let chained = vec![vec![0], vec![0, 1, 2], vec![1, 2], vec![1, 2, 3]];
+        let spare_field = vec![vec![0, 1], vec![1], vec![2, 3]];
+
+        assert_eq!(
+            Err(SolveError::Ambiguous {
+                columns: vec![0, 1, 2, 3, 4]
+            }),
+            unique_assignment(&interchangeable)
+        );
+        assert_eq!(
+            120,
+            assignments(&interchangeable, usize::MAX).unwrap().len()
+        );
+        assert_eq!(
+            Err(SolveError::Ambiguous {
+                columns: vec![1, 2]
+            }),
+            unique_assignment(&chained)
+        );
+        assert_eq!(
+            Err(SolveError::Ambiguous { columns: vec![2] }),
+            unique_assignment(&spare_field)
+        );
+    }
+
+    #[test]
+    fn day_16_names_conflicting_columns() {
+        let candidates = vec![vec![0, 1, 2], vec![1], vec![3], vec![1, 3]];
+
+        let error = unique_assignment(&candidates).unwrap_err();
+
+        assert_eq!(
+            SolveError::Unsatisfiable {
+                columns: vec![1, 2, 3],
+                fields: vec![1, 3],
+            },
+            error
+        );
+        assert_eq!(
+            "Unsatisfiable: columns 2, 3, 4 can only hold the 2 fields b, d",
+            error.describe(&["a", "b", "c", "d"])
+        );
+        assert_eq!(
+            Err(SolveError::Unsatisfiable {
+                columns: vec![1],
+                fields: vec![],
+            }),
+            assignments(&[vec![0], vec![]], 1)
+        );
+    }
+}
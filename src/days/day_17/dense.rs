@@ -0,0 +1,311 @@
+use std::collections::HashSet;
+
+use crate::days::automaton::{neighbor_deltas, Bounds, Coord, Rule};
+
+/// A two-state automaton stored as a flat array of cells covering the bounding box of the active
+/// cells. The box grows by one cell in every direction before each cycle and shrinks back to fit
+/// the active cells afterward.
+///
+/// The last `mirrored` dimensions are assumed symmetric about zero, as they are in the puzzle,
+/// where everything starts in the `z = 0` (and `w = 0`) slice. Only their non-negative half is
+/// stored, and a cell on the negative side is looked up through its reflection.
+pub struct DenseGrid<R: Rule, const N: usize> {
+    rule: R,
+    mirrored: usize,
+    bounds: Bounds<N>,
+    cells: Vec<bool>,
+    deltas: Vec<Coord<N>>,
+}
+
+impl<R: Rule, const N: usize> DenseGrid<R, N> {
+    /// Creates a new `DenseGrid` with the given cells active.
+    pub fn new(rule: R, active: &HashSet<Coord<N>>, mirrored: usize) -> Result<Self, &'static str> {
+        if rule.states() > 2 {
+            return Err("Dense grids only support rules with two states");
+        }
+        if mirrored > N {
+            return Err("Can't mirror more dimensions than the grid has");
+        }
+
+        let stored: Vec<Coord<N>> = active
+            .iter()
+            .filter(|coord| coord[N - mirrored..].iter().all(|c| *c >= 0))
+            .copied()
+            .collect();
+        let is_symmetric = active.iter().all(|coord| {
+            (N - mirrored..N).all(|d| {
+                let mut reflection = *coord;
+                reflection[d] = -reflection[d];
+                active.contains(&reflection)
+            })
+        });
+        if !is_symmetric {
+            return Err("Active cells aren't symmetric in the mirrored dimensions");
+        }
+
+        let mut grid = Self {
+            rule,
+            mirrored,
+            bounds: fit(&stored, mirrored),
+            cells: vec![],
+            deltas: neighbor_deltas(),
+        };
+        grid.cells = vec![false; volume(&grid.bounds)];
+        for coord in stored {
+            let index = grid.index(&coord).unwrap();
+            grid.cells[index] = true;
+        }
+
+        Ok(grid)
+    }
+
+    /// Finds where the given cell is stored, reflecting it into the stored half first.
+    fn index(&self, coord: &Coord<N>) -> Option<usize> {
+        let mut index = 0;
+        for d in (0..N).rev() {
+            let value = match d >= N - self.mirrored {
+                true => coord[d].abs(),
+                false => coord[d],
+            };
+            if value < self.bounds.min[d] || value > self.bounds.max[d] {
+                return None;
+            }
+            index = index * self.bounds.extent(d) + (value - self.bounds.min[d]) as usize;
+        }
+        Some(index)
+    }
+
+    /// Returns whether the cell at the given coordinates is active.
+    pub fn is_active(&self, coord: &Coord<N>) -> bool {
+        self.index(coord).is_some_and(|i| self.cells[i])
+    }
+
+    fn active_neighbors(&self, coord: &Coord<N>) -> usize {
+        self.deltas
+            .iter()
+            .filter(|delta| {
+                let mut neighbor = *coord;
+                for (n, d) in neighbor.iter_mut().zip(delta.iter()) {
+                    *n += d;
+                }
+                self.is_active(&neighbor)
+            })
+            .count()
+    }
+
+    /// Advances the grid by one cycle.
+    pub fn tick(&mut self) {
+        // The mirrored dimensions start at zero, and the cells just below it are reflections.
+        let mut grown = self.bounds;
+        for d in 0..N {
+            if d < N - self.mirrored {
+                grown.min[d] -= 1;
+            }
+            grown.max[d] += 1;
+        }
+
+        let active: Vec<Coord<N>> = (0..volume(&grown))
+            .map(|i| coord_at(&grown, i))
+            .filter(|coord| {
+                self.rule
+                    .next_state(self.is_active(coord), self.active_neighbors(coord))
+            })
+            .collect();
+
+        self.bounds = fit(&active, self.mirrored);
+        self.cells = vec![false; volume(&self.bounds)];
+        for coord in active {
+            let index = self.index(&coord).unwrap();
+            self.cells[index] = true;
+        }
+    }
+
+    pub fn run(&mut self, cycles: u32) {
+        for _ in 0..cycles {
+            self.tick();
+        }
+    }
+
+    /// Returns the number of active cells, counting each stored cell once for every reflection
+    /// of it.
+    pub fn active_count(&self) -> usize {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, active)| **active)
+            .map(|(i, _)| {
+                let coord = coord_at(&self.bounds, i);
+                1 << coord[N - self.mirrored..]
+                    .iter()
+                    .filter(|c| **c != 0)
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// Finds the bounds of the given cells, or a single cell at the origin if there are none. The
+/// bounds of the last `mirrored` dimensions always start at zero, since the cells between zero
+/// and the nearest active one can still come to life.
+fn fit<const N: usize>(coords: &[Coord<N>], mirrored: usize) -> Bounds<N> {
+    let mut bounds = Bounds::of(coords).unwrap_or(Bounds {
+        min: [0; N],
+        max: [0; N],
+    });
+    for d in N - mirrored..N {
+        bounds.min[d] = 0;
+    }
+    bounds
+}
+
+/// Returns the number of cells inside the bounds.
+fn volume<const N: usize>(bounds: &Bounds<N>) -> usize {
+    (0..N).map(|d| bounds.extent(d)).product()
+}
+
+/// Returns the coordinates of the cell at the given index of an array covering the bounds, with
+/// the first dimension varying fastest.
+fn coord_at<const N: usize>(bounds: &Bounds<N>, mut index: usize) -> Coord<N> {
+    let mut coord = [0; N];
+    for (d, value) in coord.iter_mut().enumerate() {
+        let extent = bounds.extent(d);
+        *value = bounds.min[d] + (index % extent) as i64;
+        index /= extent;
+    }
+    coord
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::grid3d::{self, Grid3d};
+    use super::super::grid4d::{self, Grid4d};
+    use super::super::CUBE_RULE;
+    use super::*;
+    use crate::days::automaton::{Automaton, Neighborhood};
+    use crate::days::life_rule::LifeRule;
+
+    const SAMPLE: [&str; 3] = [".#.", "..#", "###"];
+
+    #[test]
+    fn day_17_dense_matches_sparse_grid3d() {
+        let raw = [&SAMPLE[..]];
+        let rule: LifeRule = CUBE_RULE.parse().unwrap();
+        let mut sparse = Grid3d::new(&raw, rule.clone());
+        let mut dense = DenseGrid::new(rule, &grid3d::parse(&raw), 1).unwrap();
+
+        sparse.run(6);
+        dense.run(6);
+
+        assert_eq!(sparse.active_cube_count() as usize, dense.active_count());
+        assert_eq!(112, dense.active_count());
+    }
+
+    #[test]
+    fn day_17_dense_matches_sparse_grid4d() {
+        let raw = [&[&SAMPLE[..]][..]];
+        let rule: LifeRule = CUBE_RULE.parse().unwrap();
+        let mut sparse = Grid4d::new(&raw, rule.clone());
+        let mut dense = DenseGrid::new(rule, &grid4d::parse(&raw), 2).unwrap();
+
+        sparse.run(6);
+        dense.run(6);
+
+        assert_eq!(sparse.active_cube_count() as usize, dense.active_count());
+        assert_eq!(848, dense.active_count());
+    }
+
+    #[test]
+    fn day_17_dense_stores_only_non_negative_mirrored_half() {
+        let raw = [&SAMPLE[..]];
+        let mut dense = DenseGrid::new(
+            CUBE_RULE.parse::<LifeRule>().unwrap(),
+            &grid3d::parse(&raw),
+            1,
+        )
+        .unwrap();
+
+        dense.tick();
+
+        assert_eq!(0, dense.bounds.min[2]);
+        assert_eq!(1, dense.bounds.max[2]);
+        assert!(dense.is_active(&[-1, 0, -1]));
+        assert!(dense.is_active(&[-1, 0, 1]));
+    }
+
+    #[test]
+    fn day_17_dense_shrinks_bounds_to_active_cells() {
+        let active: HashSet<Coord<3>> = vec![[0, 0, 0], [5, 5, 0]].into_iter().collect();
+        let mut dense = DenseGrid::new(CUBE_RULE.parse::<LifeRule>().unwrap(), &active, 1).unwrap();
+
+        dense.tick();
+
+        assert_eq!(0, dense.active_count());
+        assert_eq!(
+            Bounds {
+                min: [0; 3],
+                max: [0; 3]
+            },
+            dense.bounds
+        );
+    }
+
+    #[test]
+    fn day_17_dense_keeps_slices_below_active_cells() {
+        let active: HashSet<Coord<3>> = [-2, 2]
+            .iter()
+            .flat_map(|z| vec![[0, 0, *z], [1, 0, *z], [2, 0, *z]])
+            .collect();
+        let rule: LifeRule = CUBE_RULE.parse().unwrap();
+        let mut sparse = Automaton::new(rule.clone(), Neighborhood::moore(), active.clone());
+        let mut dense = DenseGrid::new(rule, &active, 1).unwrap();
+
+        assert_eq!(0, dense.bounds.min[2]);
+        for _ in 0..4 {
+            sparse.tick();
+            dense.tick();
+            assert_eq!(sparse.active_count(), dense.active_count());
+        }
+    }
+
+    #[test]
+    fn day_17_dense_matches_sparse_on_random_starts() {
+        let rule: LifeRule = CUBE_RULE.parse().unwrap();
+        let mut seed: u64 = 2020;
+        let mut random = move |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound) as i64
+        };
+
+        for _ in 0..100 {
+            let mut active = HashSet::new();
+            for _ in 0..random(12) + 1 {
+                let (x, y, z) = (random(5), random(5), random(4));
+                active.insert([x, y, z]);
+                active.insert([x, y, -z]);
+            }
+            let mut sparse = Automaton::new(rule.clone(), Neighborhood::moore(), active.clone());
+            let mut dense = DenseGrid::new(rule.clone(), &active, 1).unwrap();
+
+            for cycle in 0..4 {
+                sparse.tick();
+                dense.tick();
+                assert_eq!(
+                    sparse.active_count(),
+                    dense.active_count(),
+                    "cycle {} from {:?}",
+                    cycle,
+                    active
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn day_17_dense_rejects_asymmetric_cells() {
+        let active: HashSet<Coord<3>> = vec![[0, 0, 1]].into_iter().collect();
+
+        assert!(DenseGrid::new(CUBE_RULE.parse::<LifeRule>().unwrap(), &active, 1).is_err());
+    }
+}
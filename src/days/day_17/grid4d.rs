@@ -1,225 +1,151 @@
 use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
-type Coord4d = (i64, i64, i64, i64);
-
-const NEIGHBOR_DELTAS: [Coord4d; 80] = [
-    (-1, -1, -1, -1),
-    (-1, -1, -1, 0),
-    (-1, -1, -1, 1),
-    (-1, -1, 0, -1),
-    (-1, -1, 0, 0),
-    (-1, -1, 0, 1),
-    (-1, -1, 1, -1),
-    (-1, -1, 1, 0),
-    (-1, -1, 1, 1),
-    (-1, 0, -1, -1),
-    (-1, 0, -1, 0),
-    (-1, 0, -1, 1),
-    (-1, 0, 0, -1),
-    (-1, 0, 0, 0),
-    (-1, 0, 0, 1),
-    (-1, 0, 1, -1),
-    (-1, 0, 1, 0),
-    (-1, 0, 1, 1),
-    (-1, 1, -1, -1),
-    (-1, 1, -1, 0),
-    (-1, 1, -1, 1),
-    (-1, 1, 0, -1),
-    (-1, 1, 0, 0),
-    (-1, 1, 0, 1),
-    (-1, 1, 1, -1),
-    (-1, 1, 1, 0),
-    (-1, 1, 1, 1),
-    (0, -1, -1, -1),
-    (0, -1, -1, 0),
-    (0, -1, -1, 1),
-    (0, -1, 0, -1),
-    (0, -1, 0, 0),
-    (0, -1, 0, 1),
-    (0, -1, 1, -1),
-    (0, -1, 1, 0),
-    (0, -1, 1, 1),
-    (0, 0, -1, -1),
-    (0, 0, -1, 0),
-    (0, 0, -1, 1),
-    (0, 0, 0, -1),
-    (0, 0, 0, 1),
-    (0, 0, 1, -1),
-    (0, 0, 1, 0),
-    (0, 0, 1, 1),
-    (0, 1, -1, -1),
-    (0, 1, -1, 0),
-    (0, 1, -1, 1),
-    (0, 1, 0, -1),
-    (0, 1, 0, 0),
-    (0, 1, 0, 1),
-    (0, 1, 1, -1),
-    (0, 1, 1, 0),
-    (0, 1, 1, 1),
-    (1, -1, -1, -1),
-    (1, -1, -1, 0),
-    (1, -1, -1, 1),
-    (1, -1, 0, -1),
-    (1, -1, 0, 0),
-    (1, -1, 0, 1),
-    (1, -1, 1, -1),
-    (1, -1, 1, 0),
-    (1, -1, 1, 1),
-    (1, 0, -1, -1),
-    (1, 0, -1, 0),
-    (1, 0, -1, 1),
-    (1, 0, 0, -1),
-    (1, 0, 0, 0),
-    (1, 0, 0, 1),
-    (1, 0, 1, -1),
-    (1, 0, 1, 0),
-    (1, 0, 1, 1),
-    (1, 1, -1, -1),
-    (1, 1, -1, 0),
-    (1, 1, -1, 1),
-    (1, 1, 0, -1),
-    (1, 1, 0, 0),
-    (1, 1, 0, 1),
-    (1, 1, 1, -1),
-    (1, 1, 1, 0),
-    (1, 1, 1, 1),
-];
+use crate::days::automaton::{Automaton, Bounds, Coord, Neighborhood};
+use crate::days::life_rule::LifeRule;
+use crate::days::render::{self, Frame};
 
-pub struct Grid4d {
-    active_coords: HashSet<Coord4d>,
-}
+type Coord4d = Coord<4>;
 
-impl Grid4d {
-    pub fn new(raw_cells: &[&[&[&str]]]) -> Self {
-        let mut active_cells: HashSet<Coord4d> = HashSet::new();
-        let oz = (raw_cells.len() / 2) as i64;
-
-        for (ib, cube) in raw_cells.iter().enumerate() {
-            let ob = (cube.len() / 2) as i64;
-            for (iz, grid) in cube.iter().enumerate() {
-                let oy = (grid.len() / 2) as i64;
-                for (iy, row) in grid
-                    .iter()
-                    .rev()
+/// Finds the active cells in the given slices, centered on the origin.
+pub fn parse(raw_cells: &[&[&[&str]]]) -> HashSet<Coord4d> {
+    let mut active_cells: HashSet<Coord4d> = HashSet::new();
+    let ob = (raw_cells.len() / 2) as i64;
+
+    for (ib, cube) in raw_cells.iter().enumerate() {
+        let oz = (cube.len() / 2) as i64;
+        for (iz, grid) in cube.iter().enumerate() {
+            let oy = (grid.len() / 2) as i64;
+            for (iy, row) in grid
+                .iter()
+                .rev()
+                .enumerate()
+                .filter(|(_, s)| s.contains('#'))
+            {
+                let ox = (row.len() / 2) as i64;
+                row.chars()
                     .enumerate()
-                    .filter(|(_, s)| s.contains('#'))
-                {
-                    let ox = (row.len() / 2) as i64;
-                    row.chars()
-                        .enumerate()
-                        .filter(|(_, c)| *c == '#')
-                        .for_each(|(ix, _)| {
-                            let x = (ix as i64) - ox;
-                            let y = (iy as i64) - oy;
-                            let z = (iz as i64) - oz;
-                            let b = (ib as i64) - ob;
-                            active_cells.insert((x, y, z, b));
-                        });
-                }
+                    .filter(|(_, c)| *c == '#')
+                    .for_each(|(ix, _)| {
+                        let x = (ix as i64) - ox;
+                        let y = (iy as i64) - oy;
+                        let z = (iz as i64) - oz;
+                        let b = (ib as i64) - ob;
+                        active_cells.insert([x, y, z, b]);
+                    });
             }
         }
-
-        Self {
-            active_coords: active_cells,
-        }
     }
 
-    pub fn tick(&mut self) {
-        let mut new_active_coords: HashSet<Coord4d> = HashSet::new();
-
-        let mut candidates: HashSet<Coord4d> = HashSet::new();
-        candidates.extend(self.active_coords.iter());
+    active_cells
+}
 
-        for coord in &self.active_coords {
-            candidates.extend(NEIGHBOR_DELTAS.iter().map(|(dx, dy, dz, db)| {
-                (coord.0 + *dx, coord.1 + *dy, coord.2 + *dz, coord.3 + *db)
-            }))
-        }
+pub struct Grid4d {
+    automaton: Automaton<LifeRule, 4>,
+}
 
-        for candidate in candidates {
-            let count = NEIGHBOR_DELTAS
-                .iter()
-                .map(|(dx, dy, dz, db)| {
-                    (
-                        candidate.0 + *dx,
-                        candidate.1 + *dy,
-                        candidate.2 + *dz,
-                        candidate.3 + *db,
-                    )
-                })
-                .filter(|c| self.active_coords.contains(c))
-                .count();
-
-            if self.active_coords.contains(&candidate) {
-                if count == 2 || count == 3 {
-                    new_active_coords.insert(candidate);
-                }
-            } else {
-                if count == 3 {
-                    new_active_coords.insert(candidate);
-                }
-            }
+impl Grid4d {
+    pub fn new(raw_cells: &[&[&[&str]]], rule: LifeRule) -> Self {
+        Self {
+            automaton: Automaton::new(rule, Neighborhood::moore(), parse(raw_cells)),
         }
+    }
 
-        self.active_coords = new_active_coords;
+    #[cfg(test)]
+    pub fn tick(&mut self) {
+        self.automaton.tick();
     }
 
+    #[cfg(test)]
     pub fn run(&mut self, cycles: u32) {
         for _ in 0..cycles {
             self.tick();
         }
     }
 
+    #[cfg(test)]
     pub fn active_cube_count(&self) -> u32 {
-        self.active_coords.len() as u32
+        self.automaton.active_count() as u32
+    }
+
+    /// Runs the given number of cycles, returning a frame and a text rendering of each.
+    pub fn frames(&mut self, cycles: usize) -> Vec<(Frame, Option<String>)> {
+        render::animate(&mut self.automaton, cycles)
+    }
+}
+
+impl Display for Grid4d {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let cells = self.automaton.cells();
+        match Bounds::of(cells.iter().map(|(coord, _)| coord)) {
+            Some(bounds) => write!(f, "{}", render::render_slices(&cells, &bounds)),
+            None => Ok(()),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::super::CUBE_RULE;
     use super::*;
 
     #[test]
     fn day_17_grid4d_parses_state() {
-        let grid = Grid4d::new(&vec![&vec![&vec![".#.", "..#", "###"][..]][..]]);
+        let grid = Grid4d::new(
+            &[&[&[".#.", "..#", "###"][..]][..]],
+            CUBE_RULE.parse().unwrap(),
+        );
         let coords: Vec<Coord4d> = vec![
-            (-1, -1, 0, 0),
-            (0, -1, 0, 0),
-            (1, -1, 0, 0),
-            (1, 0, 0, 0),
-            (0, 1, 0, 0),
+            [-1, -1, 0, 0],
+            [0, -1, 0, 0],
+            [1, -1, 0, 0],
+            [1, 0, 0, 0],
+            [0, 1, 0, 0],
         ];
         let mut expected: HashSet<Coord4d> = HashSet::new();
         for coord in coords {
             expected.insert(coord);
         }
 
-        assert_eq!(expected, grid.active_coords);
+        assert_eq!(&expected, grid.automaton.active());
     }
 
     #[test]
     fn day_17_grid4d_generates_correct_next_state() {
-        let mut grid = Grid4d::new(&vec![&vec![&vec![".#.", "..#", "###"][..]][..]]);
-        let expected = Grid4d::new(&vec![
-            &vec![
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-            ][..],
-            &vec![
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-                &vec![".....", ".....", ".#.#.", "..##.", "..#.."][..],
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-            ][..],
-            &vec![
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-                &vec![".....", ".....", ".#...", "...#.", "..#.."][..],
-            ][..],
-        ]);
+        let mut grid = Grid4d::new(
+            &[&[&[".#.", "..#", "###"][..]][..]],
+            CUBE_RULE.parse().unwrap(),
+        );
+        let expected = Grid4d::new(
+            &[
+                &[
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                ][..],
+                &[
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                    &[".....", ".....", ".#.#.", "..##.", "..#.."][..],
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                ][..],
+                &[
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                    &[".....", ".....", ".#...", "...#.", "..#.."][..],
+                ][..],
+            ],
+            CUBE_RULE.parse().unwrap(),
+        );
 
         grid.tick();
 
-        assert_eq!(expected.active_coords, grid.active_coords);
+        assert_eq!(expected.automaton.active(), grid.automaton.active());
+    }
+
+    #[test]
+    fn day_17_grid4d_labels_slices_with_z_and_w() {
+        let grid = Grid4d::new(&[&[&["#"][..]][..]], CUBE_RULE.parse().unwrap());
+
+        assert_eq!("z=0, w=0\n#\n\n", grid.to_string());
     }
 }
@@ -1,25 +1,49 @@
+mod dense;
 mod grid3d;
 mod grid4d;
 
-pub fn part_one(data: &[&str]) {
-    use self::grid3d::Grid3d;
+use std::io::Error as IoError;
+use std::path::Path;
+
+use self::dense::DenseGrid;
+use self::grid3d::Grid3d;
+use self::grid4d::Grid4d;
+use super::life_rule::LifeRule;
+use super::render::save_frames;
 
-    let data = vec![data];
-    let mut grid = Grid3d::new(&data);
+/// An active cube stays active with two or three active neighbors, and an inactive cube becomes
+/// active with exactly three.
+const CUBE_RULE: &str = "B3/S23";
+
+pub fn part_one(data: &[&str]) {
+    let data = [data];
+    let rule: LifeRule = CUBE_RULE.parse().unwrap();
+    let mut grid = DenseGrid::new(rule, &grid3d::parse(&data), 1).unwrap();
 
     grid.run(6);
 
-    println!("Active cubes after 6 cycles: {}", grid.active_cube_count());
+    println!("Active cubes after 6 cycles: {}", grid.active_count());
 }
 
 pub fn part_two(data: &[&str]) {
-    use self::grid4d::Grid4d;
-
-    let data = vec![&data[..]];
-    let data = vec![&data[..]];
-    let mut grid = Grid4d::new(&data);
+    let data = [data];
+    let data = [&data[..]];
+    let rule: LifeRule = CUBE_RULE.parse().unwrap();
+    let mut grid = DenseGrid::new(rule, &grid4d::parse(&data), 2).unwrap();
 
     grid.run(6);
 
-    println!("Active cubes after 6 cycles: {}", grid.active_cube_count());
+    println!("Active cubes after 6 cycles: {}", grid.active_count());
+}
+
+pub fn dump_frames(data: &[&str], dir: &Path, cycles: usize) -> Result<(), IoError> {
+    let rule: LifeRule = CUBE_RULE.parse().unwrap();
+
+    let cube = [data];
+    let mut grid = Grid3d::new(&cube, rule.clone());
+    save_frames(dir, "part_one", &grid.frames(cycles))?;
+
+    let hypercube = [&cube[..]];
+    let mut grid = Grid4d::new(&hypercube, rule);
+    save_frames(dir, "part_two", &grid.frames(cycles))
 }
@@ -0,0 +1,329 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The base of each digit of a `BigInt`, and of each group of decimal digits when printing one.
+const DIGIT_BITS: u32 = 32;
+const DECIMAL_GROUP: u32 = 1_000_000_000;
+
+/// An integer of any size, stored as a sign and a magnitude. The magnitude's digits are in base
+/// 2^32, least significant first, with no leading zeros, so zero has no digits and is never
+/// negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the number of bits in the magnitude, not counting leading zeros.
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(top) => self.digits.len() as u64 * DIGIT_BITS as u64 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Returns the value if it fits in a `u32`.
+    pub fn to_u32(&self) -> Option<u32> {
+        match (self.negative, &self.digits[..]) {
+            (true, _) => None,
+            (false, []) => Some(0),
+            (false, [digit]) => Some(*digit),
+            _ => None,
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::new(self.negative, add_magnitudes(&self.digits, &other.digits));
+        }
+
+        match compare_magnitudes(&self.digits, &other.digits) {
+            Ordering::Less => Self::new(
+                other.negative,
+                subtract_magnitudes(&other.digits, &self.digits),
+            ),
+            _ => Self::new(
+                self.negative,
+                subtract_magnitudes(&self.digits, &other.digits),
+            ),
+        }
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        self.add(&Self::new(!other.negative, other.digits.clone()))
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        Self::new(
+            self.negative != other.negative,
+            multiply_magnitudes(&self.digits, &other.digits),
+        )
+    }
+
+    /// Divides, rounding toward zero, and returns the quotient and remainder the way `i64` does:
+    /// the remainder takes the sign of the dividend. Returns `None` when dividing by zero.
+    pub fn divide(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = divide_magnitudes(&self.digits, &other.digits);
+        Some((
+            Self::new(self.negative != other.negative, quotient),
+            Self::new(self.negative, remainder),
+        ))
+    }
+
+    /// Reads a number written in decimal, which must be made only of ASCII digits, nine digits
+    /// at a time.
+    pub fn from_decimal(digits: &str) -> Self {
+        let mut magnitude = vec![];
+        for group in digits.as_bytes().chunks(9) {
+            let scale = 10u32.pow(group.len() as u32);
+            let value = group
+                .iter()
+                .fold(0, |value, digit| value * 10 + (digit - b'0') as u32);
+            magnitude = add_magnitudes(&multiply_magnitudes(&magnitude, &[scale]), &[value]);
+        }
+        Self::new(false, magnitude)
+    }
+
+    /// Raises the number to a power by repeated squaring.
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut result = Self::from(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.multiply(&base);
+            }
+        }
+        result
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        Self::new(
+            value < 0,
+            vec![magnitude as u32, (magnitude >> DIGIT_BITS) as u32],
+        )
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut groups = vec![];
+        let mut magnitude = self.digits.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = divide_by_digit(&magnitude, DECIMAL_GROUP);
+            groups.push(remainder);
+            magnitude = quotient;
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut groups = groups.iter().rev();
+        write!(f, "{}", groups.next().unwrap())?;
+        for group in groups {
+            write!(f, "{:09}", group)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> DIGIT_BITS;
+    }
+    result.push(carry as u32);
+    result
+}
+
+/// Subtracts `b` from `a`, which must be at least as large.
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, digit) in a.iter().enumerate() {
+        let difference = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (difference < 0) as i64;
+        result.push((difference + (borrow << DIGIT_BITS)) as u32);
+    }
+    result
+}
+
+fn multiply_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let product = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> DIGIT_BITS;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+fn divide_by_digit(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0u64;
+    for (i, digit) in a.iter().enumerate().rev() {
+        let current = (remainder << DIGIT_BITS) | *digit as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    (quotient, remainder as u32)
+}
+
+/// Divides `a` by `b`, which mustn't be zero, one bit at a time.
+fn divide_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = b {
+        let (quotient, remainder) = divide_by_digit(a, *divisor);
+        return (quotient, vec![remainder]);
+    }
+
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for i in (0..a.len() * DIGIT_BITS as usize).rev() {
+        let bit = (a[i / DIGIT_BITS as usize] >> (i % DIGIT_BITS as usize)) & 1;
+        remainder = add_magnitudes(&remainder, &remainder);
+        remainder[0] |= bit;
+        while remainder.last() == Some(&0) {
+            remainder.pop();
+        }
+
+        if compare_magnitudes(&remainder, b) != Ordering::Less {
+            remainder = subtract_magnitudes(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[i / DIGIT_BITS as usize] |= 1 << (i % DIGIT_BITS as usize);
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn big(value: i128) -> BigInt {
+        let magnitude = value.unsigned_abs();
+        BigInt::new(
+            value < 0,
+            (0..4).map(|i| (magnitude >> (32 * i)) as u32).collect(),
+        )
+    }
+
+    const VALUES: [i128; 9] = [
+        0,
+        1,
+        -1,
+        7,
+        -4_294_967_296,
+        123_456_789_012_345_678,
+        -98_765_432_109_876_543_210,
+        i64::MAX as i128,
+        i64::MIN as i128,
+    ];
+
+    #[test]
+    fn day_18_bigint_matches_i128_arithmetic() {
+        for a in &VALUES {
+            for b in &VALUES {
+                assert_eq!(big(a + b), big(*a).add(&big(*b)), "{} + {}", a, b);
+                assert_eq!(big(a - b), big(*a).subtract(&big(*b)), "{} - {}", a, b);
+                if a.abs() < 1 << 60 && b.abs() < 1 << 60 {
+                    assert_eq!(big(a * b), big(*a).multiply(&big(*b)), "{} * {}", a, b);
+                }
+                if *b != 0 {
+                    assert_eq!(
+                        Some((big(a / b), big(a % b))),
+                        big(*a).divide(&big(*b)),
+                        "{} / {}",
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+        assert_eq!(None, big(5).divide(&big(0)));
+    }
+
+    #[test]
+    fn day_18_bigint_prints_in_decimal() {
+        for value in &VALUES {
+            assert_eq!(value.to_string(), big(*value).to_string());
+        }
+        assert_eq!(
+            "1267650600228229401496703205376",
+            BigInt::from(2).pow(100).to_string()
+        );
+        assert_eq!("-125", BigInt::from(-5).pow(3).to_string());
+        assert_eq!(
+            "1267650600228229401496703205376",
+            BigInt::from_decimal("0001267650600228229401496703205376").to_string()
+        );
+        assert!(BigInt::from_decimal("0").is_zero());
+        assert_eq!(101, BigInt::from(2).pow(100).bits());
+        assert_eq!(0, BigInt::from(0).bits());
+    }
+
+    #[test]
+    fn day_18_bigint_divides_by_large_divisors() {
+        let a = BigInt::from(3).pow(100);
+        let b = BigInt::from(7).pow(30);
+
+        let (quotient, remainder) = a.divide(&b).unwrap();
+
+        assert_eq!(a, quotient.multiply(&b).add(&remainder));
+        assert_eq!(
+            Ordering::Less,
+            compare_magnitudes(&remainder.digits, &b.digits)
+        );
+    }
+}
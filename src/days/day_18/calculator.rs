@@ -0,0 +1,273 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{BufRead, Error as IoError, Write};
+use std::str::FromStr;
+
+use super::bigint::BigInt;
+use super::expr::Expr;
+use super::number::{ArithmeticError, Number};
+use super::parser::parse;
+use super::precedence::{PrecedenceTable, TABLES};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Mode(Option<String>),
+    Tree,
+    Steps,
+    Exact,
+    Help,
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum CommandError {
+    Unrecognized(String),
+    UnknownMode(String),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Unrecognized(command) => write!(f, "Unrecognized command: {}", command),
+            Self::UnknownMode(mode) => write!(f, "Unknown mode: {}", mode),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+
+        match parts.first().copied() {
+            Some(":mode") | Some(":m") => match parts.get(1) {
+                Some(name) if PrecedenceTable::named(name).is_none() => {
+                    Err(CommandError::UnknownMode((*name).to_owned()))
+                }
+                name => Ok(Command::Mode(name.map(|name| (*name).to_owned()))),
+            },
+            Some(":tree") | Some(":t") => Ok(Command::Tree),
+            Some(":steps") | Some(":s") => Ok(Command::Steps),
+            Some(":exact") | Some(":e") => Ok(Command::Exact),
+            Some(":help") | Some(":h") => Ok(Command::Help),
+            Some(":quit") | Some(":q") => Ok(Command::Quit),
+            _ => Err(CommandError::Unrecognized(s.to_owned())),
+        }
+    }
+}
+
+const HELP: &str = "\
+<expression>    evaluate an expression using + - * / % ^ and parentheses
+:mode [name]    show the modes, or switch to one: left-to-right, addition-first or standard
+:tree           toggle showing how each expression's operators are grouped
+:steps          toggle showing each operation as it's applied
+:exact          toggle arbitrary precision instead of checked 64-bit arithmetic
+:quit           end the session";
+
+/// Evaluates expressions under a chosen precedence table.
+struct Calculator {
+    table: &'static PrecedenceTable,
+    tree: bool,
+    steps: bool,
+    exact: bool,
+}
+
+impl Calculator {
+    /// Carries out a command, returning whether the session should go on.
+    fn execute(&mut self, command: Command, out: &mut dyn Write) -> Result<bool, IoError> {
+        match command {
+            Command::Mode(None) => {
+                for table in TABLES.iter() {
+                    let marker = if table.name == self.table.name {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    writeln!(out, "{} {}", marker, table.name)?;
+                }
+            }
+            Command::Mode(Some(name)) => {
+                // Commands only name modes that exist.
+                self.table = PrecedenceTable::named(&name).unwrap();
+                writeln!(out, "Mode: {}", self.table.name)?;
+            }
+            Command::Tree => {
+                self.tree = !self.tree;
+                writeln!(out, "Showing parse trees: {}", on_off(self.tree))?;
+            }
+            Command::Steps => {
+                self.steps = !self.steps;
+                writeln!(out, "Showing steps: {}", on_off(self.steps))?;
+            }
+            Command::Exact => {
+                self.exact = !self.exact;
+                writeln!(out, "Arbitrary precision: {}", on_off(self.exact))?;
+            }
+            Command::Help => writeln!(out, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn calculate(&self, expression: &str, out: &mut dyn Write) -> Result<(), IoError> {
+        let expr = match parse(expression, self.table) {
+            Ok(expr) => expr,
+            Err(e) => return writeln!(out, "{}", e),
+        };
+
+        if self.tree {
+            write!(out, "{}", expr.tree())?;
+        }
+        if self.exact {
+            self.evaluate::<BigInt>(&expr, out)
+        } else {
+            self.evaluate::<i64>(&expr, out)
+        }
+    }
+
+    fn evaluate<N: Number>(&self, expr: &Expr, out: &mut dyn Write) -> Result<(), IoError> {
+        let mut steps = vec![];
+        let result = expr.evaluate_with::<N>(&mut |operation, lhs, rhs, result| {
+            steps.push(format!(
+                "{} {} {} = {}",
+                lhs,
+                operation.symbol(),
+                rhs,
+                result
+            ));
+        });
+
+        if self.steps {
+            for step in &steps {
+                writeln!(out, "  {}", step)?;
+            }
+        }
+        match result {
+            Ok(value) => writeln!(out, "= {}", value),
+            Err(e) if e.error == ArithmeticError::Overflow && !self.exact => {
+                writeln!(out, "{} (use :exact for arbitrary precision)", e)
+            }
+            Err(e) => writeln!(out, "{}", e),
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Reads expressions and commands from `input` until it runs out or a `:quit` command, writing
+/// results to `out`. An interactive session prompts for each line; otherwise each line is echoed
+/// so a scripted session's output can be read on its own.
+pub fn run_session(
+    table: &'static PrecedenceTable,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    interactive: bool,
+) -> Result<(), IoError> {
+    let mut calculator = Calculator {
+        table,
+        tree: false,
+        steps: false,
+        exact: false,
+    };
+
+    loop {
+        if interactive {
+            write!(out, "({}) ", calculator.table.name)?;
+            out.flush()?;
+        }
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !interactive {
+            writeln!(out, "> {}", line)?;
+        }
+
+        if !line.starts_with(':') {
+            calculator.calculate(line, out)?;
+            continue;
+        }
+        match line.parse() {
+            Ok(command) => {
+                if !calculator.execute(command, out)? {
+                    return Ok(());
+                }
+            }
+            Err(e) => writeln!(out, "{}", e)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::precedence::LEFT_TO_RIGHT;
+    use super::*;
+
+    fn run_script(script: &str) -> String {
+        let mut out = vec![];
+
+        run_session(&LEFT_TO_RIGHT, &mut script.as_bytes(), &mut out, false).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn day_18_calculator_parses_commands() {
+        assert_eq!(Ok(Command::Mode(None)), ":mode".parse());
+        assert_eq!(
+            Ok(Command::Mode(Some("standard".to_string()))),
+            ":m standard".parse()
+        );
+        assert_eq!(
+            Err(CommandError::UnknownMode("reverse".to_string())),
+            ":mode reverse".parse::<Command>()
+        );
+        assert_eq!(
+            Err(CommandError::Unrecognized(":undo".to_string())),
+            ":undo".parse::<Command>()
+        );
+    }
+
+    #[test]
+    fn day_18_calculator_switches_modes() {
+        let output = run_script("1 + 2 * 3\n:mode addition-first\n2 * 3 + 4\n:mode\n");
+
+        assert_eq!(
+            "> 1 + 2 * 3\n= 9\n> :mode addition-first\nMode: addition-first\n\
+             > 2 * 3 + 4\n= 14\n> :mode\n  left-to-right\n* addition-first\n  standard\n",
+            output
+        );
+    }
+
+    #[test]
+    fn day_18_calculator_shows_tree_and_steps() {
+        let output = run_script(":mode standard\n:tree\n:steps\n1 + 2 * 3\n");
+
+        assert!(output
+            .ends_with("> 1 + 2 * 3\n+\n  1\n  *\n    2\n    3\n  2 * 3 = 6\n  1 + 6 = 7\n= 7\n"));
+    }
+
+    #[test]
+    fn day_18_calculator_reports_errors_and_overflow() {
+        let output = run_script("1 +\n2 ^ 70\n:exact\n2 ^ 70\n:quit\n1 + 1\n");
+
+        assert_eq!(
+            "> 1 +\nUnexpected end of expression at column 4, expected a number or '('\n\
+             > 2 ^ 70\nOverflow at columns 1-6 (use :exact for arbitrary precision)\n\
+             > :exact\nArbitrary precision: on\n> 2 ^ 70\n= 1180591620717411303424\n> :quit\n",
+            output
+        );
+    }
+}
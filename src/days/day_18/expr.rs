@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::number::{ArithmeticError, Number};
+use super::token::{Operation, Span};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExprKind {
+    /// The digits of a number.
+    Number(String),
+    Binary(Operation, Box<Expr>, Box<Expr>),
+}
+
+/// A parsed expression, along with the part of the source it came from. The span of a binary
+/// expression runs from the start of its left operand to the end of its right one.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    /// Evaluates the expression in the given number type, stopping at the first operation that
+    /// fails.
+    pub fn evaluate<N: Number>(&self) -> Result<N, EvalError> {
+        self.evaluate_with(&mut |_, _, _, _| {})
+    }
+
+    /// Evaluates the expression like `evaluate`, calling `on_step` with each operation as it's
+    /// applied, along with its operands and result.
+    pub fn evaluate_with<N: Number>(
+        &self,
+        on_step: &mut dyn FnMut(Operation, &N, &N, &N),
+    ) -> Result<N, EvalError> {
+        match &self.kind {
+            ExprKind::Number(digits) => N::from_digits(digits).map_err(|error| EvalError {
+                error,
+                span: self.span,
+            }),
+            ExprKind::Binary(operation, lhs, rhs) => {
+                let lhs = lhs.evaluate_with(on_step)?;
+                let rhs = rhs.evaluate_with(on_step)?;
+                let result = N::apply(*operation, &lhs, &rhs).map_err(|error| EvalError {
+                    error,
+                    span: self.span,
+                })?;
+                on_step(*operation, &lhs, &rhs, &result);
+                Ok(result)
+            }
+        }
+    }
+
+    /// Draws the expression as a tree, one node per line, with each operation's operands
+    /// indented beneath it.
+    pub fn tree(&self) -> String {
+        let mut result = String::new();
+        self.write_tree(&mut result, 0);
+        result
+    }
+
+    fn write_tree(&self, result: &mut String, depth: usize) {
+        result.push_str(&"  ".repeat(depth));
+        match &self.kind {
+            ExprKind::Number(number) => result.push_str(&format!("{}\n", number)),
+            ExprKind::Binary(operation, lhs, rhs) => {
+                result.push_str(&format!("{}\n", operation.symbol()));
+                lhs.write_tree(result, depth + 1);
+                rhs.write_tree(result, depth + 1);
+            }
+        }
+    }
+}
+
+impl Display for Expr {
+    /// Writes the expression with every operation in parentheses, which shows how the
+    /// precedence table it was parsed under grouped the operators.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match &self.kind {
+            ExprKind::Number(number) => write!(f, "{}", number),
+            ExprKind::Binary(operation, lhs, rhs) => {
+                write!(f, "({} {} {})", lhs, operation.symbol(), rhs)
+            }
+        }
+    }
+}
+
+/// An operation that failed, along with the span of the expression it was applied in.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct EvalError {
+    pub error: ArithmeticError,
+    pub span: Span,
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} at columns {}-{}",
+            self.error,
+            self.span.start + 1,
+            self.span.end
+        )
+    }
+}
@@ -0,0 +1,294 @@
+mod bigint;
+mod calculator;
+mod expr;
+mod number;
+mod parser;
+mod precedence;
+mod token;
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{BufRead, Error as IoError, Write};
+
+use self::bigint::BigInt;
+use self::expr::EvalError;
+use self::number::{ArithmeticError, Number};
+use self::parser::{parse, ParseError};
+use self::precedence::{PrecedenceTable, ADDITION_FIRST, LEFT_TO_RIGHT};
+use self::token::Operation;
+
+pub fn part_one(data: &[&str]) {
+    print_sum(data, &LEFT_TO_RIGHT);
+}
+
+pub fn part_two(data: &[&str]) {
+    print_sum(data, &ADDITION_FIRST);
+}
+
+/// Runs the calculator, starting in the named mode. See `calculator::run_session`.
+pub fn calculate(
+    mode: &str,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    interactive: bool,
+) -> Result<(), IoError> {
+    match PrecedenceTable::named(mode) {
+        Some(table) => calculator::run_session(table, input, out, interactive),
+        None => writeln!(out, "Unknown mode: {}", mode),
+    }
+}
+
+/// Prints the sum of the lines, reporting every line that fails. If the only failures are
+/// overflows, the sum is worked out again with arbitrary precision.
+fn print_sum(data: &[&str], table: &PrecedenceTable) {
+    let errors = match sum::<i64>(data, table) {
+        Ok(sum) => {
+            println!("Sum: {}", sum);
+            return;
+        }
+        Err(errors) => errors,
+    };
+
+    print_errors(&errors);
+    if errors.iter().all(|(_, error)| error.is_overflow()) {
+        println!("Recalculating with arbitrary precision");
+        match sum::<BigInt>(data, table) {
+            Ok(sum) => println!("Sum: {}", sum),
+            Err(errors) => print_errors(&errors),
+        }
+    }
+}
+
+fn print_errors(errors: &[(usize, LineError)]) {
+    for (i, error) in errors {
+        println!("Line {}: {}", i + 1, error);
+    }
+}
+
+/// Adds up the value of every line, carrying on past failures so that every failing line is
+/// reported.
+fn sum<N: Number>(data: &[&str], table: &PrecedenceTable) -> Result<N, Vec<(usize, LineError)>> {
+    let mut sum = Some(N::from(0));
+    let mut errors = vec![];
+
+    for (i, line) in data.iter().enumerate() {
+        match evaluate::<N>(line, table) {
+            Ok(value) => {
+                if let Some(total) = &sum {
+                    sum = N::apply(Operation::Add, total, &value).ok();
+                    if sum.is_none() {
+                        errors.push((i, LineError::SumOverflow));
+                    }
+                }
+            }
+            Err(error) => errors.push((i, error)),
+        }
+    }
+
+    match sum {
+        Some(sum) if errors.is_empty() => Ok(sum),
+        _ => Err(errors),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LineError {
+    Parse(ParseError),
+    Eval(EvalError),
+    /// The line evaluated, but adding it to the lines before it overflowed.
+    SumOverflow,
+}
+
+impl LineError {
+    fn is_overflow(&self) -> bool {
+        match self {
+            Self::Eval(error) => error.error == ArithmeticError::Overflow,
+            Self::SumOverflow => true,
+            Self::Parse(_) => false,
+        }
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Parse(error) => write!(f, "{}", error),
+            Self::Eval(error) => write!(f, "{}", error),
+            Self::SumOverflow => write!(f, "Sum overflows when this line is added"),
+        }
+    }
+}
+
+impl From<ParseError> for LineError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<EvalError> for LineError {
+    fn from(error: EvalError) -> Self {
+        Self::Eval(error)
+    }
+}
+
+/// Evaluates an expression in the given number type, grouping operators according to the table.
+fn evaluate<N: Number>(expression: &str, table: &PrecedenceTable) -> Result<N, LineError> {
+    Ok(parse(expression, table)?.evaluate()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::precedence::STANDARD;
+    use super::*;
+
+    #[test]
+    fn day_18_evaluates_simple_expression() {
+        assert_eq!(5, evaluate::<i64>("2 + 3", &LEFT_TO_RIGHT).unwrap())
+    }
+
+    #[test]
+    fn day_18_evaluates_long_expression() {
+        assert_eq!(
+            71,
+            evaluate::<i64>("1 + 2 * 3 + 4 * 5 + 6", &LEFT_TO_RIGHT).unwrap()
+        )
+    }
+
+    #[test]
+    fn day_18_evaluates_long_expressions_with_parentheses() {
+        assert_eq!(
+            26,
+            evaluate::<i64>("2 * 3 + (4 * 5)", &LEFT_TO_RIGHT).unwrap()
+        );
+        assert_eq!(
+            437,
+            evaluate::<i64>("5 + (8 * 3 + 9 + 3 * 4 * 3)", &LEFT_TO_RIGHT).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_18_evaluates_expressions_with_nested_parentheses() {
+        assert_eq!(
+            51,
+            evaluate::<i64>("1 + (2 * 3) + (4 * (5 + 6))", &LEFT_TO_RIGHT).unwrap()
+        );
+        assert_eq!(
+            12240,
+            evaluate::<i64>("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", &LEFT_TO_RIGHT).unwrap()
+        );
+        assert_eq!(
+            13632,
+            evaluate::<i64>(
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                &LEFT_TO_RIGHT
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn day_18_evaluates_expressions_with_precedence() {
+        assert_eq!(
+            231,
+            evaluate::<i64>("1 + 2 * 3 + 4 * 5 + 6", &ADDITION_FIRST).unwrap()
+        );
+        assert_eq!(
+            51,
+            evaluate::<i64>("1 + (2 * 3) + (4 * (5 + 6))", &ADDITION_FIRST).unwrap()
+        );
+        assert_eq!(
+            46,
+            evaluate::<i64>("2 * 3 + (4 * 5)", &ADDITION_FIRST).unwrap()
+        );
+        assert_eq!(
+            1445,
+            evaluate::<i64>("5 + (8 * 3 + 9 + 3 * 4 * 3)", &ADDITION_FIRST).unwrap()
+        );
+        assert_eq!(
+            669060,
+            evaluate::<i64>("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", &ADDITION_FIRST).unwrap()
+        );
+        assert_eq!(
+            23340,
+            evaluate::<i64>(
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                &ADDITION_FIRST
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn day_18_evaluates_every_operator() {
+        assert_eq!(
+            3,
+            evaluate::<i64>("7 - 2 * 2 + 20 / 5 % 3 - 1", &STANDARD).unwrap()
+        );
+        assert_eq!(
+            -12,
+            evaluate::<i64>("10 - 2 * 8 - 6 / 3 * 2 + 4 % 3 - 3", &STANDARD).unwrap()
+        );
+        assert_eq!(
+            -1,
+            evaluate::<i64>("7 - 2 * 2 + 20 / 5 % 3 - 1", &LEFT_TO_RIGHT).unwrap()
+        );
+        assert_eq!(
+            0,
+            evaluate::<i64>("7 - 2 * 2 + 20 / 5 % 3 - 1", &ADDITION_FIRST).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_18_groups_powers_by_associativity() {
+        assert_eq!(512, evaluate::<i64>("2 ^ 3 ^ 2", &STANDARD).unwrap());
+        assert_eq!(64, evaluate::<i64>("2 ^ 3 ^ 2", &LEFT_TO_RIGHT).unwrap());
+        assert_eq!(19, evaluate::<i64>("3 + 2 ^ 4", &STANDARD).unwrap());
+        assert_eq!(625, evaluate::<i64>("3 + 2 ^ 4", &LEFT_TO_RIGHT).unwrap());
+        assert_eq!(48, evaluate::<i64>("3 * 2 ^ 4", &STANDARD).unwrap());
+    }
+
+    #[test]
+    fn day_18_reports_overflow_per_line() {
+        let data = [
+            "2 * 3",
+            "4611686018427387904 * 2",
+            "1 + 4611686018427387904 + 4611686018427387904",
+            "5 / (2 - 2)",
+        ];
+
+        let errors = sum::<i64>(&data, &LEFT_TO_RIGHT).unwrap_err();
+
+        let lines: Vec<usize> = errors.iter().map(|(i, _)| *i).collect();
+        assert_eq!(vec![1, 2, 3], lines);
+        assert_eq!("Overflow at columns 1-23", errors[0].1.to_string());
+        assert!(errors[1].1.is_overflow());
+        assert_eq!("Division by zero at columns 1-10", errors[2].1.to_string());
+    }
+
+    #[test]
+    fn day_18_sums_exactly_with_arbitrary_precision() {
+        let data = ["9223372036854775807", "9223372036854775807", "2 ^ 64"];
+
+        let errors = sum::<i64>(&data, &ADDITION_FIRST).unwrap_err();
+
+        assert_eq!((1, LineError::SumOverflow), errors[0]);
+        assert_eq!("Overflow at columns 1-6", errors[1].1.to_string());
+        assert_eq!(
+            "36893488147419103230",
+            sum::<BigInt>(&data, &ADDITION_FIRST).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn day_18_reads_literals_too_large_for_machine_words() {
+        let data = ["99999999999999999999 * 2 + 1"];
+
+        let errors = sum::<i64>(&data, &STANDARD).unwrap_err();
+
+        assert_eq!("Overflow at columns 1-20", errors[0].1.to_string());
+        assert!(errors[0].1.is_overflow());
+        assert_eq!(
+            "199999999999999999999",
+            sum::<BigInt>(&data, &STANDARD).unwrap().to_string()
+        );
+    }
+}
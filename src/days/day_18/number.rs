@@ -0,0 +1,170 @@
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::bigint::BigInt;
+use super::token::Operation;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ArithmeticError {
+    Overflow,
+    DivisionByZero,
+    NegativeExponent,
+    ExponentTooLarge,
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Overflow => write!(f, "Overflow"),
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::NegativeExponent => write!(f, "Negative exponent"),
+            Self::ExponentTooLarge => write!(f, "Exponent too large"),
+        }
+    }
+}
+
+/// The most bits a power may have in exact arithmetic. Anything larger takes too long to work
+/// out and print to be useful.
+const MAX_POWER_BITS: u64 = 1 << 16;
+
+/// A type expressions can be evaluated in.
+pub trait Number: From<i64> + Display + Sized {
+    /// Converts a number written in decimal, failing if it doesn't fit.
+    fn from_digits(digits: &str) -> Result<Self, ArithmeticError>;
+
+    fn apply(operation: Operation, lhs: &Self, rhs: &Self) -> Result<Self, ArithmeticError>;
+}
+
+/// Checked machine arithmetic, which fails rather than wrapping when a result doesn't fit.
+impl Number for i64 {
+    fn from_digits(digits: &str) -> Result<Self, ArithmeticError> {
+        digits.parse().map_err(|_| ArithmeticError::Overflow)
+    }
+
+    fn apply(operation: Operation, lhs: &Self, rhs: &Self) -> Result<Self, ArithmeticError> {
+        let (lhs, rhs) = (*lhs, *rhs);
+        let result = match operation {
+            Operation::Add => lhs.checked_add(rhs),
+            Operation::Subtract => lhs.checked_sub(rhs),
+            Operation::Multiply => lhs.checked_mul(rhs),
+            Operation::Divide | Operation::Remainder if rhs == 0 => {
+                return Err(ArithmeticError::DivisionByZero)
+            }
+            Operation::Divide => lhs.checked_div(rhs),
+            Operation::Remainder => lhs.checked_rem(rhs),
+            Operation::Power if rhs < 0 => return Err(ArithmeticError::NegativeExponent),
+            Operation::Power => {
+                let exponent = u32::try_from(rhs).map_err(|_| ArithmeticError::ExponentTooLarge)?;
+                lhs.checked_pow(exponent)
+            }
+        };
+        result.ok_or(ArithmeticError::Overflow)
+    }
+}
+
+/// Exact arithmetic, which only fails on operations that have no integer result.
+impl Number for BigInt {
+    fn from_digits(digits: &str) -> Result<Self, ArithmeticError> {
+        Ok(BigInt::from_decimal(digits))
+    }
+
+    fn apply(operation: Operation, lhs: &Self, rhs: &Self) -> Result<Self, ArithmeticError> {
+        match operation {
+            Operation::Add => Ok(lhs.add(rhs)),
+            Operation::Subtract => Ok(lhs.subtract(rhs)),
+            Operation::Multiply => Ok(lhs.multiply(rhs)),
+            Operation::Divide => lhs
+                .divide(rhs)
+                .map(|(quotient, _)| quotient)
+                .ok_or(ArithmeticError::DivisionByZero),
+            Operation::Remainder => lhs
+                .divide(rhs)
+                .map(|(_, remainder)| remainder)
+                .ok_or(ArithmeticError::DivisionByZero),
+            Operation::Power if rhs.is_negative() => Err(ArithmeticError::NegativeExponent),
+            Operation::Power => {
+                let exponent = rhs.to_u32().ok_or(ArithmeticError::ExponentTooLarge)?;
+                // A base of b bits raised to the exponent has more than (b - 1) * exponent bits,
+                // which only says nothing for bases of -1, 0 and 1, whose powers stay small.
+                let bits = lhs.bits().saturating_sub(1) * exponent as u64;
+                if bits > MAX_POWER_BITS {
+                    return Err(ArithmeticError::ExponentTooLarge);
+                }
+                Ok(lhs.pow(exponent))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_18_checks_machine_arithmetic() {
+        assert_eq!(
+            Err(ArithmeticError::Overflow),
+            i64::apply(Operation::Multiply, &i64::MAX, &2)
+        );
+        assert_eq!(
+            Err(ArithmeticError::Overflow),
+            i64::apply(Operation::Divide, &i64::MIN, &-1)
+        );
+        assert_eq!(
+            Err(ArithmeticError::DivisionByZero),
+            i64::apply(Operation::Remainder, &5, &0)
+        );
+        assert_eq!(
+            Err(ArithmeticError::NegativeExponent),
+            i64::apply(Operation::Power, &2, &-1)
+        );
+        assert_eq!(Ok(-8), i64::apply(Operation::Power, &-2, &3));
+        assert_eq!(Ok(i64::MAX), i64::from_digits("9223372036854775807"));
+        assert_eq!(
+            Err(ArithmeticError::Overflow),
+            i64::from_digits("9223372036854775808")
+        );
+    }
+
+    #[test]
+    fn day_18_big_arithmetic_is_exact() {
+        let big = BigInt::from(i64::MAX);
+
+        assert_eq!(
+            "85070591730234615847396907784232501249",
+            BigInt::apply(Operation::Multiply, &big, &big)
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            Err(ArithmeticError::DivisionByZero),
+            BigInt::apply(Operation::Divide, &big, &BigInt::from(0))
+        );
+        assert_eq!(
+            Err(ArithmeticError::ExponentTooLarge),
+            BigInt::apply(Operation::Power, &big, &big)
+        );
+        assert_eq!(
+            Err(ArithmeticError::ExponentTooLarge),
+            BigInt::apply(
+                Operation::Power,
+                &BigInt::from(3),
+                &BigInt::from(4_000_000_000)
+            )
+        );
+        assert_eq!(
+            Ok(BigInt::from(-1)),
+            BigInt::apply(
+                Operation::Power,
+                &BigInt::from(-1),
+                &BigInt::from(4_000_000_001)
+            )
+        );
+        assert_eq!(
+            65537,
+            BigInt::apply(Operation::Power, &BigInt::from(2), &BigInt::from(65536))
+                .unwrap()
+                .bits()
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::iter::Peekable;
+use std::slice::Iter;
+
+use super::expr::{Expr, ExprKind};
+use super::precedence::{Associativity, PrecedenceTable};
+use super::token::{tokenize, Span, Token};
+
+const OPERAND: &str = "a number or '('";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedCharacter {
+        character: char,
+        position: usize,
+    },
+    UnexpectedToken {
+        token: Token,
+        span: Span,
+        expected: &'static str,
+    },
+    UnexpectedEnd {
+        position: usize,
+        expected: &'static str,
+    },
+    UnclosedParen {
+        span: Span,
+    },
+    UnmatchedParen {
+        span: Span,
+    },
+}
+
+impl Display for ParseError {
+    /// Describes the error, giving positions as one-based columns.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnexpectedCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "Unexpected character '{}' at column {}",
+                character,
+                position + 1
+            ),
+            Self::UnexpectedToken {
+                token,
+                span,
+                expected,
+            } => write!(
+                f,
+                "Unexpected '{}' at column {}, expected {}",
+                token,
+                span.start + 1,
+                expected
+            ),
+            Self::UnexpectedEnd { position, expected } => write!(
+                f,
+                "Unexpected end of expression at column {}, expected {}",
+                position + 1,
+                expected
+            ),
+            Self::UnclosedParen { span } => {
+                write!(f, "Unclosed '(' at column {}", span.start + 1)
+            }
+            Self::UnmatchedParen { span } => {
+                write!(f, "Unmatched ')' at column {}", span.start + 1)
+            }
+        }
+    }
+}
+
+/// Parses an expression, grouping operators according to the table.
+pub fn parse(expression: &str, table: &PrecedenceTable) -> Result<Expr, ParseError> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser {
+        tokens: tokens.iter().peekable(),
+        table,
+        end: expression.len(),
+    };
+
+    let result = parser.expression(0)?;
+    match parser.tokens.next() {
+        None => Ok(result),
+        Some((Token::CloseParen, span)) => Err(ParseError::UnmatchedParen { span: *span }),
+        Some((token, span)) => Err(ParseError::UnexpectedToken {
+            token: token.clone(),
+            span: *span,
+            expected: "an operator",
+        }),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Peekable<Iter<'a, (Token, Span)>>,
+    table: &'a PrecedenceTable,
+    end: usize,
+}
+
+impl Parser<'_> {
+    /// Parses tokens up to the first operator that binds less tightly than `min_precedence`,
+    /// using precedence climbing. Each operator's right operand takes every following operator
+    /// that binds more tightly, or just as tightly when the operator is right-associative.
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut result = self.operand()?;
+
+        while let Some((Token::Op(operation), _)) = self.tokens.peek() {
+            let binding = self.table.binding(*operation);
+            if binding.precedence < min_precedence {
+                break;
+            }
+            self.tokens.next();
+
+            let next_precedence = match binding.associativity {
+                Associativity::Left => binding.precedence + 1,
+                Associativity::Right => binding.precedence,
+            };
+            let rhs = self.expression(next_precedence)?;
+            let span = result.span.to(rhs.span);
+            result = Expr {
+                kind: ExprKind::Binary(*operation, Box::new(result), Box::new(rhs)),
+                span,
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// Parses a number or a parenthesized expression.
+    fn operand(&mut self) -> Result<Expr, ParseError> {
+        match self.tokens.next() {
+            Some((Token::Number(number), span)) => Ok(Expr {
+                kind: ExprKind::Number(number.clone()),
+                span: *span,
+            }),
+            Some((Token::OpenParen, open)) => {
+                let result = self.expression(0)?;
+                match self.tokens.next() {
+                    Some((Token::CloseParen, _)) => Ok(result),
+                    Some((token, span)) => Err(ParseError::UnexpectedToken {
+                        token: token.clone(),
+                        span: *span,
+                        expected: "an operator or ')'",
+                    }),
+                    None => Err(ParseError::UnclosedParen { span: *open }),
+                }
+            }
+            Some((token, span)) => Err(ParseError::UnexpectedToken {
+                token: token.clone(),
+                span: *span,
+                expected: OPERAND,
+            }),
+            None => Err(ParseError::UnexpectedEnd {
+                position: self.end,
+                expected: OPERAND,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::precedence::{ADDITION_FIRST, LEFT_TO_RIGHT, STANDARD};
+    use super::super::token::Operation;
+    use super::*;
+
+    #[test]
+    fn day_18_builds_tree_with_spans() {
+        let expr = parse("1 + (2 * 3)", &LEFT_TO_RIGHT).unwrap();
+
+        let number = |value: i64, start| Expr {
+            kind: ExprKind::Number(value.to_string()),
+            span: Span {
+                start,
+                end: start + 1,
+            },
+        };
+        let product = Expr {
+            kind: ExprKind::Binary(
+                Operation::Multiply,
+                Box::new(number(2, 5)),
+                Box::new(number(3, 9)),
+            ),
+            span: Span { start: 5, end: 10 },
+        };
+        assert_eq!(
+            Expr {
+                kind: ExprKind::Binary(Operation::Add, Box::new(number(1, 0)), Box::new(product)),
+                span: Span { start: 0, end: 10 },
+            },
+            expr
+        );
+    }
+
+    #[test]
+    fn day_18_prints_grouping_of_each_table() {
+        let expression = "1 + 2 * 3 + 4 ^ 2 ^ 1";
+
+        let print = |table| parse(expression, table).unwrap().to_string();
+
+        assert_eq!("(((((1 + 2) * 3) + 4) ^ 2) ^ 1)", print(&LEFT_TO_RIGHT));
+        assert_eq!("((((1 + 2) * (3 + 4)) ^ 2) ^ 1)", print(&ADDITION_FIRST));
+        assert_eq!("((1 + (2 * 3)) + (4 ^ (2 ^ 1)))", print(&STANDARD));
+    }
+
+    #[test]
+    fn day_18_reports_unbalanced_parentheses() {
+        assert_eq!(
+            Err(ParseError::UnclosedParen {
+                span: Span { start: 4, end: 5 }
+            }),
+            parse("1 + (2 * 3", &LEFT_TO_RIGHT)
+        );
+        assert_eq!(
+            Err(ParseError::UnmatchedParen {
+                span: Span { start: 5, end: 6 }
+            }),
+            parse("1 + 2) * 3", &LEFT_TO_RIGHT)
+        );
+    }
+
+    #[test]
+    fn day_18_reports_unexpected_tokens() {
+        assert_eq!(
+            "Unexpected '*' at column 5, expected a number or '('",
+            parse("1 + * 3", &LEFT_TO_RIGHT).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unexpected '4' at column 8, expected an operator or ')'",
+            parse("(1 + 3 4)", &LEFT_TO_RIGHT).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unexpected '(' at column 3, expected an operator",
+            parse("2 (3)", &LEFT_TO_RIGHT).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unexpected end of expression at column 4, expected a number or '('",
+            parse("2 +", &LEFT_TO_RIGHT).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unexpected end of expression at column 1, expected a number or '('",
+            parse("", &LEFT_TO_RIGHT).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use super::token::Operation;
+
+/// Which way a chain of operators with the same precedence groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` means `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` means `a ^ (b ^ c)`.
+    Right,
+}
+
+/// How tightly an operator binds. Operators with a higher precedence are applied first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub operation: Operation,
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+/// A set of rules for grouping operators. Evaluating under a different table only requires a
+/// new table.
+pub struct PrecedenceTable {
+    pub name: &'static str,
+    pub bindings: [Binding; 6],
+}
+
+impl PrecedenceTable {
+    /// Returns the table with the given name.
+    pub fn named(name: &str) -> Option<&'static Self> {
+        TABLES.iter().copied().find(|table| table.name == name)
+    }
+
+    pub fn binding(&self, operation: Operation) -> Binding {
+        *self
+            .bindings
+            .iter()
+            .find(|binding| binding.operation == operation)
+            .unwrap()
+    }
+}
+
+const fn left(operation: Operation, precedence: u8) -> Binding {
+    Binding {
+        operation,
+        precedence,
+        associativity: Associativity::Left,
+    }
+}
+
+const fn right(operation: Operation, precedence: u8) -> Binding {
+    Binding {
+        operation,
+        precedence,
+        associativity: Associativity::Right,
+    }
+}
+
+/// Every table, in the order they're listed to users.
+pub const TABLES: [&PrecedenceTable; 3] = [&LEFT_TO_RIGHT, &ADDITION_FIRST, &STANDARD];
+
+/// Every operator has the same precedence, so they're applied from left to right, as in part
+/// one.
+pub const LEFT_TO_RIGHT: PrecedenceTable = PrecedenceTable {
+    name: "left-to-right",
+    bindings: [
+        left(Operation::Add, 1),
+        left(Operation::Subtract, 1),
+        left(Operation::Multiply, 1),
+        left(Operation::Divide, 1),
+        left(Operation::Remainder, 1),
+        left(Operation::Power, 1),
+    ],
+};
+
+/// Addition and subtraction are applied before everything else, as in part two.
+pub const ADDITION_FIRST: PrecedenceTable = PrecedenceTable {
+    name: "addition-first",
+    bindings: [
+        left(Operation::Add, 2),
+        left(Operation::Subtract, 2),
+        left(Operation::Multiply, 1),
+        left(Operation::Divide, 1),
+        left(Operation::Remainder, 1),
+        left(Operation::Power, 1),
+    ],
+};
+
+/// The conventional order of operations.
+pub const STANDARD: PrecedenceTable = PrecedenceTable {
+    name: "standard",
+    bindings: [
+        left(Operation::Add, 1),
+        left(Operation::Subtract, 1),
+        left(Operation::Multiply, 2),
+        left(Operation::Divide, 2),
+        left(Operation::Remainder, 2),
+        right(Operation::Power, 3),
+    ],
+};
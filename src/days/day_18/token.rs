@@ -0,0 +1,259 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::parser::ParseError;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Token {
+    /// The digits of a number, which is only converted once the type it's evaluated in is known.
+    Number(String),
+    Op(Operation),
+    OpenParen,
+    CloseParen,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Op(operation) => write!(f, "{}", operation.symbol()),
+            Self::OpenParen => write!(f, "("),
+            Self::CloseParen => write!(f, ")"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Operation {
+    /// Returns the operation a character stands for.
+    fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Subtract),
+            '*' => Some(Self::Multiply),
+            '/' => Some(Self::Divide),
+            '%' => Some(Self::Remainder),
+            '^' => Some(Self::Power),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Subtract => '-',
+            Self::Multiply => '*',
+            Self::Divide => '/',
+            Self::Remainder => '%',
+            Self::Power => '^',
+        }
+    }
+}
+
+/// The byte offsets of the start and end of a piece of an expression.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Returns the span from the start of this one to the end of the other.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+        }
+    }
+}
+
+/// Splits an expression into tokens, along with where each one came from.
+pub fn tokenize(expression: &str) -> Result<Vec<(Token, Span)>, ParseError> {
+    let mut result = vec![];
+
+    let mut iter = expression.char_indices().peekable();
+
+    while let Some(&(start, c)) = iter.peek() {
+        let token = match c {
+            '0'..='9' => {
+                result.push(read_number(expression, &mut iter));
+                continue;
+            }
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            c if c.is_whitespace() => {
+                iter.next();
+                continue;
+            }
+            c => match Operation::from_symbol(c) {
+                Some(operation) => Token::Op(operation),
+                None => {
+                    return Err(ParseError::UnexpectedCharacter {
+                        character: c,
+                        position: start,
+                    })
+                }
+            },
+        };
+
+        iter.next();
+        result.push((
+            token,
+            Span {
+                start,
+                end: start + c.len_utf8(),
+            },
+        ));
+    }
+
+    Ok(result)
+}
+
+fn read_number(expression: &str, iter: &mut Peekable<CharIndices>) -> (Token, Span) {
+    let start = iter.peek().map_or(expression.len(), |(i, _)| *i);
+    let mut end = start;
+
+    while let Some(&(i, c)) = iter.peek() {
+        if c.is_ascii_digit() {
+            end = i + c.len_utf8();
+            iter.next();
+        } else {
+            break;
+        }
+    }
+
+    (
+        Token::Number(expression[start..end].to_string()),
+        Span { start, end },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn number(digits: &str) -> Token {
+        Token::Number(digits.to_string())
+    }
+
+    fn parse(expression: &str) -> Vec<Token> {
+        tokenize(expression)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    #[test]
+    fn day_18_parses_simple_expression() {
+        let tokens = vec![number("123"), Token::Op(Operation::Add), number("345")];
+
+        assert_eq!(tokens, parse("123 + 345"));
+    }
+
+    #[test]
+    fn day_18_parses_long_expression() {
+        let tokens = vec![
+            number("123"),
+            Token::Op(Operation::Add),
+            number("345"),
+            Token::Op(Operation::Multiply),
+            number("3"),
+            Token::Op(Operation::Add),
+            number("42"),
+            Token::Op(Operation::Add),
+            number("1"),
+            Token::Op(Operation::Multiply),
+            number("234"),
+        ];
+
+        assert_eq!(tokens, parse("123 + 345 * 3 + 42 + 1 * 234"));
+    }
+
+    #[test]
+    fn day_18_parses_expression_with_parentheses() {
+        let tokens = vec![
+            number("123"),
+            Token::Op(Operation::Add),
+            Token::OpenParen,
+            number("345"),
+            Token::Op(Operation::Multiply),
+            number("3"),
+            Token::Op(Operation::Add),
+            number("42"),
+            Token::CloseParen,
+            Token::Op(Operation::Add),
+            number("1"),
+            Token::Op(Operation::Multiply),
+            number("234"),
+        ];
+
+        assert_eq!(tokens, parse("123 + (345 * 3 + 42) + 1 * 234"));
+    }
+
+    #[test]
+    fn day_18_parses_expression_with_nested_parentheses() {
+        let tokens = vec![
+            number("123"),
+            Token::Op(Operation::Add),
+            Token::OpenParen,
+            number("345"),
+            Token::Op(Operation::Multiply),
+            Token::OpenParen,
+            number("3"),
+            Token::Op(Operation::Add),
+            number("42"),
+            Token::CloseParen,
+            Token::CloseParen,
+            Token::Op(Operation::Add),
+            number("1"),
+            Token::Op(Operation::Multiply),
+            number("234"),
+        ];
+
+        assert_eq!(tokens, parse("123 + (345 * (3 + 42)) + 1 * 234"));
+    }
+
+    #[test]
+    fn day_18_tokenizes_with_spans() {
+        assert_eq!(
+            Ok(vec![
+                (Token::OpenParen, Span { start: 0, end: 1 }),
+                (number("12"), Span { start: 1, end: 3 }),
+                (Token::Op(Operation::Power), Span { start: 4, end: 5 }),
+                (number("3"), Span { start: 6, end: 7 }),
+                (Token::CloseParen, Span { start: 7, end: 8 }),
+            ]),
+            tokenize("(12 ^ 3)")
+        );
+    }
+
+    #[test]
+    fn day_18_rejects_unknown_characters_and_keeps_huge_numbers() {
+        assert_eq!(
+            Err(ParseError::UnexpectedCharacter {
+                character: 'x',
+                position: 4
+            }),
+            tokenize("2 + x")
+        );
+        assert_eq!(
+            vec![
+                number("1"),
+                Token::Op(Operation::Add),
+                number("99999999999999999999")
+            ],
+            parse("1 + 99999999999999999999")
+        );
+    }
+}
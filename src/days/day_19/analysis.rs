@@ -0,0 +1,257 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::generator::{find_languages, sequence_language};
+use super::{Rules, Symbol};
+
+/// The most strings a rule's language may hold before the ambiguity check gives up on it.
+const LANGUAGE_LIMIT: usize = 10_000;
+
+/// Two alternatives of a rule that both match the same string, so the rule can match it in more
+/// than one way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub rule: usize,
+    pub alternatives: (usize, usize),
+    /// The shortest string both alternatives match.
+    pub example: String,
+}
+
+/// The problems found in a rule set, with every list in rule order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Each rule that refers to an undefined rule, along with the rule it refers to.
+    pub undefined: Vec<(usize, usize)>,
+    /// Rules that can refer back to themselves before matching any text.
+    pub left_recursive: Vec<usize>,
+    /// Rules the start rule never refers to, directly or not.
+    pub unreachable: Vec<usize>,
+    pub ambiguities: Vec<Ambiguity>,
+    /// Rules that match too many strings up to the length bound to check for ambiguity.
+    pub unchecked: Vec<usize>,
+}
+
+impl Analysis {
+    /// Analyzes a rule set without matching anything against it. Ambiguities are only looked
+    /// for among strings up to `max_len` long.
+    pub fn new(rules: &Rules, start: usize, max_len: usize) -> Self {
+        let ids: BTreeSet<usize> = rules.rules.keys().copied().collect();
+        let nullable = rules.nullable();
+
+        let undefined = ids
+            .iter()
+            .flat_map(|id| {
+                rules
+                    .references(*id)
+                    .filter(|other| !rules.rules.contains_key(other))
+                    .map(move |other| (*id, other))
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let left_recursive = ids
+            .iter()
+            .copied()
+            .filter(|id| reaches(*id, *id, |rule| left_corners(rules, rule, &nullable)))
+            .collect();
+
+        let reachable = rules.reachable(start);
+        let unreachable = ids
+            .iter()
+            .copied()
+            .filter(|id| !reachable.contains(id))
+            .collect();
+
+        let (ambiguities, unchecked) = find_ambiguities(rules, max_len);
+
+        Self {
+            undefined,
+            left_recursive,
+            unreachable,
+            ambiguities,
+            unchecked,
+        }
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let list = |rules: &[usize]| -> String {
+            let rules: Vec<String> = rules.iter().map(usize::to_string).collect();
+            rules.join(" ")
+        };
+
+        writeln!(f, "Undefined references:")?;
+        for (rule, other) in &self.undefined {
+            writeln!(f, "  rule {} refers to rule {}", rule, other)?;
+        }
+        writeln!(f, "Left-recursive rules: {}", list(&self.left_recursive))?;
+        writeln!(f, "Unreachable rules: {}", list(&self.unreachable))?;
+        writeln!(f, "Ambiguous alternatives:")?;
+        for ambiguity in &self.ambiguities {
+            writeln!(
+                f,
+                "  rule {}, alternatives {} and {} both match {:?}",
+                ambiguity.rule,
+                ambiguity.alternatives.0 + 1,
+                ambiguity.alternatives.1 + 1,
+                ambiguity.example
+            )?;
+        }
+        if !self.unchecked.is_empty() {
+            writeln!(
+                f,
+                "Too many strings to check for ambiguity: {}",
+                list(&self.unchecked)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists the rules a rule can start with: in each alternative, every rule reference up to and
+/// including the first symbol that can't match the empty string.
+fn left_corners(rules: &Rules, id: usize, nullable: &HashSet<usize>) -> Vec<usize> {
+    let mut result = vec![];
+    let alternatives = rules.rules.get(&id).map(|spec| &spec.alternatives[..]);
+    for symbols in alternatives.unwrap_or_default() {
+        for symbol in symbols {
+            match symbol {
+                Symbol::Rule(other) => {
+                    result.push(*other);
+                    if !nullable.contains(other) {
+                        break;
+                    }
+                }
+                Symbol::Literal(literal) if literal.is_empty() => {}
+                Symbol::Literal(_) => break,
+            }
+        }
+    }
+    result
+}
+
+/// Returns whether `to` can be reached from `from` by taking at least one edge.
+fn reaches<F>(from: usize, to: usize, edges: F) -> bool
+where
+    F: Fn(usize) -> Vec<usize>,
+{
+    let mut seen = HashSet::new();
+    let mut stack = edges(from);
+    while let Some(rule) = stack.pop() {
+        if rule == to {
+            return true;
+        }
+        if seen.insert(rule) {
+            stack.extend(edges(rule));
+        }
+    }
+    false
+}
+
+/// Compares the alternatives of every rule for strings they both match, returning the
+/// ambiguities found along with the rules that couldn't be checked.
+fn find_ambiguities(rules: &Rules, max_len: usize) -> (Vec<Ambiguity>, Vec<usize>) {
+    let languages = find_languages(rules, max_len, LANGUAGE_LIMIT);
+    let mut ambiguities = vec![];
+    let mut unchecked = vec![];
+
+    for (id, language) in &languages {
+        let alternatives = &rules.rules[id].alternatives;
+        if alternatives.len() < 2 {
+            continue;
+        }
+        if language.is_none() {
+            unchecked.push(*id);
+            continue;
+        }
+
+        let strings: Vec<BTreeSet<String>> = alternatives
+            .iter()
+            .map(|symbols| {
+                sequence_language(symbols, &languages, max_len, LANGUAGE_LIMIT).unwrap_or_default()
+            })
+            .collect();
+        for i in 0..strings.len() {
+            for j in i + 1..strings.len() {
+                let example = strings[i]
+                    .intersection(&strings[j])
+                    .min_by_key(|s| (s.len(), s.to_string()));
+                if let Some(example) = example {
+                    ambiguities.push(Ambiguity {
+                        rule: *id,
+                        alternatives: (i, j),
+                        example: example.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    (ambiguities, unchecked)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn analyze(raw_rules: &[&str]) -> Analysis {
+        let mut rules = Rules::new();
+        rules.add_rules(raw_rules).unwrap();
+        Analysis::new(&rules, 0, 6)
+    }
+
+    #[test]
+    fn day_19_finds_undefined_and_unreachable_rules() {
+        let analysis = analyze(&[
+            "0: 1 2 | 3 | 4 \"a\"",
+            "1: \"a\"",
+            "3: 1 4",
+            "5: \"b\"",
+            "6: 5",
+        ]);
+
+        assert_eq!(vec![(0, 2), (0, 4), (3, 4)], analysis.undefined);
+        assert_eq!(vec![5, 6], analysis.unreachable);
+    }
+
+    #[test]
+    fn day_19_finds_left_recursion_through_empty_rules() {
+        let analysis = analyze(&[
+            "0: 1 0 \"b\" | \"a\"",
+            "1: \"c\" |",
+            "2: 2 \"a\"",
+            "3: \"a\" 3 |",
+        ]);
+
+        assert_eq!(vec![0, 2], analysis.left_recursive);
+    }
+
+    #[test]
+    fn day_19_finds_ambiguous_alternatives() {
+        let analysis = analyze(&["0: 1 1 | 2", "1: \"a\" | \"aa\"", "2: \"aaa\" | \"b\""]);
+
+        assert_eq!(
+            vec![Ambiguity {
+                rule: 0,
+                alternatives: (0, 1),
+                example: String::from("aaa"),
+            }],
+            analysis.ambiguities
+        );
+        assert!(analysis.unchecked.is_empty());
+    }
+
+    #[test]
+    fn day_19_reports_clean_rule_set() {
+        let analysis = analyze(&["0: 1 2 | 2 1", "1: \"a\"", "2: \"b\""]);
+
+        assert_eq!(
+            "Undefined references:\nLeft-recursive rules: \nUnreachable rules: \n\
+             Ambiguous alternatives:\n",
+            analysis.to_string()
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
+
+use super::{Rules, Symbol};
+
+/// Every string up to a length bound that some symbols match, or `None` if there are more than
+/// a limit of them.
+pub type Language = Option<BTreeSet<String>>;
+
+/// Finds the strings matched by a sequence of symbols, given the languages of the rules.
+pub fn sequence_language(
+    symbols: &[Symbol],
+    languages: &BTreeMap<usize, Language>,
+    max_len: usize,
+    limit: usize,
+) -> Language {
+    let mut result: BTreeSet<String> = iter::once(String::new()).collect();
+    for symbol in symbols {
+        let empty = BTreeSet::new();
+        let single;
+        let strings = match symbol {
+            Symbol::Rule(other) => match languages.get(other) {
+                Some(language) => language.as_ref()?,
+                None => &empty,
+            },
+            Symbol::Literal(literal) => {
+                single = iter::once(literal.clone()).collect();
+                &single
+            }
+        };
+
+        result = result
+            .iter()
+            .flat_map(|prefix| {
+                strings
+                    .iter()
+                    .filter(move |s| prefix.len() + s.len() <= max_len)
+                    .map(move |s| format!("{}{}", prefix, s))
+            })
+            .collect();
+        if result.len() > limit {
+            return None;
+        }
+    }
+    Some(result)
+}
+
+/// Works out the languages of every rule, by growing them from nothing until they stop
+/// changing. Recursive rules reach a fixed point because no language holds strings past the
+/// length bound.
+pub fn find_languages(rules: &Rules, max_len: usize, limit: usize) -> BTreeMap<usize, Language> {
+    let mut languages: BTreeMap<usize, Language> = rules
+        .rules
+        .keys()
+        .map(|id| (*id, Some(BTreeSet::new())))
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (id, spec) in &rules.rules {
+            if languages[id].is_none() {
+                continue;
+            }
+
+            let mut language = Some(BTreeSet::new());
+            for symbols in &spec.alternatives {
+                language = match (
+                    language,
+                    sequence_language(symbols, &languages, max_len, limit),
+                ) {
+                    (Some(mut strings), Some(more)) => {
+                        strings.extend(more);
+                        Some(strings).filter(|strings| strings.len() <= limit)
+                    }
+                    _ => None,
+                };
+            }
+
+            if language != languages[id] {
+                languages.insert(*id, language);
+                changed = true;
+            }
+        }
+    }
+
+    languages
+}
+
+/// A xorshift generator, so samples can be repeated from a seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Xorshift gets stuck at zero.
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number below `bound`, which mustn't be zero. The slight bias towards small
+    /// numbers doesn't matter for sampling test strings.
+    fn below(&mut self, bound: u128) -> u128 {
+        ((self.next() as u128) << 64 | self.next() as u128) % bound
+    }
+
+    /// Picks an index with probability proportional to its weight. The weights mustn't all be
+    /// zero, and their total must fit in a `u128`.
+    fn choose(&mut self, weights: &[u128]) -> usize {
+        let mut pick = self.below(checked_sum(weights).expect("weights overflow"));
+        for (i, weight) in weights.iter().enumerate() {
+            if pick < *weight {
+                return i;
+            }
+            pick -= weight;
+        }
+        unreachable!()
+    }
+}
+
+/// Produces the strings up to a length bound that the rules match.
+pub struct Generator<'a> {
+    rules: &'a Rules,
+    max_len: usize,
+    /// For each rule, the number of ways it matches strings of each length up to the bound.
+    counts: BTreeMap<usize, Vec<u128>>,
+}
+
+impl<'a> Generator<'a> {
+    /// Counts the ways each rule can match strings of each length, failing if a count, or a
+    /// rule's total over every length, doesn't fit in a `u128`, or if a rule can match a string
+    /// in infinitely many ways.
+    pub fn new(rules: &'a Rules, max_len: usize) -> Result<Self, String> {
+        let lengths = max_len
+            .checked_add(1)
+            .ok_or_else(|| format!("Maximum length is too large: {}", max_len))?;
+        let mut generator = Self {
+            rules,
+            max_len,
+            counts: rules
+                .rules
+                .keys()
+                .map(|id| (*id, vec![0; lengths]))
+                .collect(),
+        };
+        generator.count_all()?;
+        if generator.counts.values().any(|c| checked_sum(c).is_none()) {
+            return Err(too_many());
+        }
+        Ok(generator)
+    }
+
+    /// Returns the number of ways the rule matches strings of any length up to the bound, or
+    /// `None` if it isn't defined.
+    pub fn total(&self, rule: usize) -> Option<u128> {
+        // Every rule's total was checked when the generator was built.
+        self.counts
+            .get(&rule)
+            .map(|counts| checked_sum(counts).unwrap())
+    }
+
+    /// Fills in the counts one length at a time. Counts for shorter strings are final by the
+    /// time a length is reached, but rules can depend on each other at the same length, so
+    /// each length repeats until its counts stop changing. They settle within one round per
+    /// rule unless a cycle of rules matches the same string over and over.
+    fn count_all(&mut self) -> Result<(), String> {
+        for len in 0..=self.max_len {
+            for round in 0..=self.rules.rules.len() {
+                let mut changed = None;
+                for (id, spec) in &self.rules.rules {
+                    let mut count: u128 = 0;
+                    for symbols in &spec.alternatives {
+                        count = count
+                            .checked_add(self.sequence_counts(symbols)?[len])
+                            .ok_or_else(too_many)?;
+                    }
+                    if count != self.counts[id][len] {
+                        self.counts.get_mut(id).unwrap()[len] = count;
+                        changed = Some(*id);
+                    }
+                }
+
+                match changed {
+                    None => break,
+                    Some(id) if round == self.rules.rules.len() => {
+                        return Err(format!(
+                            "Rule {} matches strings of length {} in infinitely many ways",
+                            id, len
+                        ))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the ways a sequence of symbols matches strings of each length, by working out the
+    /// counts for each suffix of the sequence from the last symbol back.
+    fn sequence_counts(&self, symbols: &[Symbol]) -> Result<Vec<u128>, String> {
+        let mut result = vec![0; self.max_len + 1];
+        result[0] = 1;
+
+        for symbol in symbols.iter().rev() {
+            let counts = self.symbol_counts(symbol);
+            let mut combined = vec![0u128; self.max_len + 1];
+            for (first, first_count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+                for (rest, rest_count) in result[..=self.max_len - first].iter().enumerate() {
+                    combined[first + rest] = first_count
+                        .checked_mul(*rest_count)
+                        .and_then(|count| count.checked_add(combined[first + rest]))
+                        .ok_or_else(too_many)?;
+                }
+            }
+            result = combined;
+        }
+
+        Ok(result)
+    }
+
+    fn symbol_counts(&self, symbol: &Symbol) -> Vec<u128> {
+        match symbol {
+            Symbol::Rule(other) => match self.counts.get(other) {
+                Some(counts) => counts.clone(),
+                None => vec![0; self.max_len + 1],
+            },
+            Symbol::Literal(literal) => {
+                let mut counts = vec![0; self.max_len + 1];
+                if literal.len() <= self.max_len {
+                    counts[literal.len()] = 1;
+                }
+                counts
+            }
+        }
+    }
+
+    /// Returns the number of ways the rule matches strings of each length up to the bound.
+    /// That's the number of strings it matches, as long as it isn't ambiguous.
+    pub fn counts(&self, rule: usize) -> Option<&[u128]> {
+        self.counts.get(&rule).map(Vec::as_slice)
+    }
+
+    /// Lists every string up to the bound that the rule matches, shortest first and in order
+    /// within each length, failing if the rule matches in more than `limit` ways. Only one
+    /// length's strings are held at a time.
+    pub fn strings(
+        &self,
+        rule: usize,
+        limit: u128,
+    ) -> Result<impl Iterator<Item = String> + '_, String> {
+        let total = self.total(rule).unwrap_or(0);
+        if total > limit {
+            return Err(format!(
+                "Too many strings to list: rule {} matches in {} ways, more than {}",
+                rule, total, limit
+            ));
+        }
+
+        Ok((0..=self.max_len).flat_map(move |len| {
+            let strings: BTreeSet<String> = self.rule_strings(rule, len).into_iter().collect();
+            strings
+        }))
+    }
+
+    /// Lists the strings of the given length that the rule matches, once for each way it
+    /// matches them. Only the lengths the counts allow are tried, so nothing is built that
+    /// doesn't end up in a match.
+    fn rule_strings(&self, rule: usize, len: usize) -> Vec<String> {
+        if self.counts.get(&rule).map_or(0, |counts| counts[len]) == 0 {
+            return vec![];
+        }
+
+        self.rules.rules[&rule]
+            .alternatives
+            .iter()
+            .flat_map(|symbols| self.sequence_strings(symbols, len))
+            .collect()
+    }
+
+    fn sequence_strings(&self, symbols: &[Symbol], len: usize) -> Vec<String> {
+        let (first, rest) = match symbols.split_first() {
+            Some(split) => split,
+            None if len == 0 => return vec![String::new()],
+            None => return vec![],
+        };
+
+        let first_counts = self.symbol_counts(first);
+        let rest_counts = self.checked_sequence_counts(rest);
+        let mut result = vec![];
+        for first_len in 0..=len {
+            if first_counts[first_len] == 0 || rest_counts[len - first_len] == 0 {
+                continue;
+            }
+            let heads = match first {
+                Symbol::Rule(other) => self.rule_strings(*other, first_len),
+                Symbol::Literal(literal) => vec![literal.clone()],
+            };
+            let tails = self.sequence_strings(rest, len - first_len);
+            for head in &heads {
+                result.extend(tails.iter().map(|tail| format!("{}{}", head, tail)));
+            }
+        }
+        result
+    }
+
+    /// Picks a random string up to the bound that the rule matches, or `None` if it doesn't
+    /// match any. Every way of matching a string is equally likely.
+    pub fn sample(&self, rule: usize, rng: &mut Rng) -> Option<String> {
+        let counts = self.counts.get(&rule)?;
+        if counts.iter().all(|count| *count == 0) {
+            return None;
+        }
+
+        let len = rng.choose(counts);
+        let mut result = String::new();
+        self.expand_rule(rule, len, rng, &mut result);
+        Some(result)
+    }
+
+    fn expand_rule(&self, rule: usize, len: usize, rng: &mut Rng, result: &mut String) {
+        let alternatives = &self.rules.rules[&rule].alternatives;
+        let weights: Vec<u128> = alternatives
+            .iter()
+            .map(|symbols| self.checked_sequence_counts(symbols)[len])
+            .collect();
+        let symbols = &alternatives[rng.choose(&weights)];
+        self.expand_sequence(symbols, len, rng, result);
+    }
+
+    /// Appends a random string of the given length matching the symbols, by choosing how much
+    /// of it the first symbol matches in proportion to the ways of matching the rest.
+    fn expand_sequence(&self, symbols: &[Symbol], len: usize, rng: &mut Rng, result: &mut String) {
+        let (first, rest) = match symbols.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+
+        let first_counts = self.symbol_counts(first);
+        let rest_counts = self.checked_sequence_counts(rest);
+        let weights: Vec<u128> = (0..=len)
+            .map(|first_len| first_counts[first_len] * rest_counts[len - first_len])
+            .collect();
+        let first_len = rng.choose(&weights);
+
+        match first {
+            Symbol::Rule(other) => self.expand_rule(*other, first_len, rng, result),
+            Symbol::Literal(literal) => result.push_str(literal),
+        }
+        self.expand_sequence(rest, len - first_len, rng, result);
+    }
+
+    fn checked_sequence_counts(&self, symbols: &[Symbol]) -> Vec<u128> {
+        // Every suffix of every alternative was counted without overflowing when the generator
+        // was built.
+        self.sequence_counts(symbols).unwrap()
+    }
+}
+
+/// Adds up the values, or returns `None` if the total doesn't fit in a `u128`.
+fn checked_sum(values: &[u128]) -> Option<u128> {
+    values
+        .iter()
+        .try_fold(0u128, |total, value| total.checked_add(*value))
+}
+
+fn too_many() -> String {
+    String::from("Too many strings to count")
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::matcher::Matcher;
+    use super::super::LOOPING_RULES;
+    use super::*;
+
+    const SAMPLE_RULES: [&str; 6] = [
+        "0: 4 1 5",
+        "1: 2 3 | 3 2",
+        "2: 4 4 | 5 5",
+        "3: 4 5 | 5 4",
+        "4: \"a\"",
+        "5: \"b\"",
+    ];
+
+    fn rules(raw_rules: &[&str]) -> Rules {
+        let mut rules = Rules::new();
+        rules.add_rules(raw_rules).unwrap();
+        rules
+    }
+
+    /// Lists every string of `a`s and `b`s up to the given length.
+    fn all_strings(max_len: usize) -> Vec<String> {
+        let mut result = vec![String::new()];
+        let mut start = 0;
+        for _ in 0..max_len {
+            let end = result.len();
+            for i in start..end {
+                for c in &["a", "b"] {
+                    let next = format!("{}{}", result[i], c);
+                    result.push(next);
+                }
+            }
+            start = end;
+        }
+        result
+    }
+
+    #[test]
+    fn day_19_enumerates_and_counts_matching_strings() -> Result<(), String> {
+        let rules = rules(&SAMPLE_RULES);
+        let generator = Generator::new(&rules, 6)?;
+
+        let strings: Vec<String> = generator.strings(0, 8)?.collect();
+
+        assert_eq!(8, strings.len());
+        assert!(strings.contains(&String::from("aaaabb")));
+        assert!(generator.strings(0, 7).is_err());
+        assert_eq!(Some(&[0, 0, 0, 0, 0, 0, 8][..]), generator.counts(0));
+        assert_eq!(Some(&[0, 0, 2, 0, 0, 0, 0][..]), generator.counts(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_counts_recursive_rules() -> Result<(), String> {
+        let rules = rules(&["0: 1 | 1 0", "1: \"a\" | \"bb\""]);
+        let generator = Generator::new(&rules, 5)?;
+
+        // Fibonacci: each string ends in either "a" or "bb".
+        assert_eq!(Some(&[0, 1, 2, 3, 5, 8][..]), generator.counts(0));
+        let strings: Vec<String> = generator.strings(0, 19)?.collect();
+        assert_eq!(19, strings.len());
+        assert_eq!(vec!["a", "aa", "bb"], strings[..3]);
+
+        let looping = self::rules(&["0: 1 | 0", "1: \"a\""]);
+        assert!(Generator::new(&looping, 3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_rejects_totals_too_large_to_count() -> Result<(), String> {
+        // Each length has 2^127 ways to match, which fits, but the two lengths together don't.
+        let doubled = vec!["1"; 127].join(" ");
+        let rules = rules(&[
+            &format!("0: {} | {} \"b\"", doubled, doubled),
+            "1: \"a\" | \"a\"",
+        ]);
+
+        assert_eq!(Some(1 << 127), Generator::new(&rules, 127)?.total(0));
+        assert_eq!(Err(too_many()), Generator::new(&rules, 128).map(|_| ()));
+        assert!(Generator::new(&rules, usize::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_samples_matching_strings() -> Result<(), String> {
+        let rules = rules(&SAMPLE_RULES);
+        let generator = Generator::new(&rules, 6)?;
+        let strings: BTreeSet<String> = generator.strings(0, 8)?.collect();
+        let mut rng = Rng::new(19);
+
+        let samples: BTreeSet<String> = (0..200)
+            .map(|_| generator.sample(0, &mut rng).unwrap())
+            .collect();
+
+        assert_eq!(strings, samples);
+        assert_eq!(None, Generator::new(&rules, 5)?.sample(0, &mut rng));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_regex_and_direct_matchers_agree() {
+        let mut rules = rules(&SAMPLE_RULES);
+        let strings = all_strings(8);
+
+        let check = |rules: &Rules, depth| {
+            let matcher = Matcher::new(rules, 0).unwrap();
+            let regex = rules.to_regex(0, depth);
+            let generator = Generator::new(rules, 8).unwrap();
+            let expected: BTreeSet<String> = generator.strings(0, u128::MAX).unwrap().collect();
+            for s in &strings {
+                assert_eq!(expected.contains(s), matcher.matches(s), "{}", s);
+                assert_eq!(expected.contains(s), regex.is_match(s), "{}", s);
+            }
+        };
+
+        check(&rules, 0);
+        rules
+            .add_rules(&["0: 8 11", "42: 4 | 5 5", "31: 5 | 4 4"])
+            .unwrap();
+        rules.add_rules(&LOOPING_RULES).unwrap();
+        check(&rules, 8);
+    }
+
+    #[test]
+    fn day_19_direct_matcher_accepts_random_samples() -> Result<(), String> {
+        let rules = rules(&[
+            "0: 8 11",
+            "8: 42 | 42 8",
+            "11: 42 31 | 42 11 31",
+            "42: \"ab\" 1 | \"b\"",
+            "31: 1 \"a\" | \"bb\"",
+            "1: \"a\" | \"b\" | 1 1",
+        ]);
+        let generator = Generator::new(&rules, 30)?;
+        let matcher = Matcher::new(&rules, 0)?;
+        let mut rng = Rng::new(2020);
+
+        for _ in 0..100 {
+            let sample = generator.sample(0, &mut rng).unwrap();
+            assert!(matcher.matches(&sample), "{}", sample);
+            assert!(sample.len() <= 30);
+        }
+
+        Ok(())
+    }
+}
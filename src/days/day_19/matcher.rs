@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Rules, Symbol};
+
+/// One piece of an alternative: another rule, given by its index in the matcher, or literal
+/// text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Rule(usize),
+    Literal(String),
+}
+
+/// A partly matched alternative: the terms of `rules[rule][alternative]` before `dot` have
+/// matched the message from `origin` up to the chart position the item is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    rule: usize,
+    alternative: usize,
+    dot: usize,
+    origin: usize,
+}
+
+/// A rule set compiled for matching messages directly, with an Earley recognizer, so it handles
+/// any recursion, including left recursion, without unrolling it. Every rule becomes a list of
+/// alternatives, each a sequence of terms, with references to other rules resolved up front.
+pub struct Matcher {
+    rules: Vec<Vec<Vec<Term>>>,
+    /// Whether each rule can match the empty string.
+    nullable: Vec<bool>,
+    start: usize,
+}
+
+impl Matcher {
+    /// Compiles the rules the start rule can reach, matching messages against the start rule.
+    /// Rules it can't reach are left out, so they can't stop it compiling.
+    pub fn new(rules: &Rules, start: usize) -> Result<Self, String> {
+        let mut ids: Vec<usize> = rules.reachable(start).into_iter().collect();
+        ids.sort_unstable();
+        let index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let compiled = ids
+            .iter()
+            .map(|id| {
+                rules.rules[id]
+                    .alternatives
+                    .iter()
+                    .map(|symbols| {
+                        symbols
+                            .iter()
+                            .map(|symbol| match symbol {
+                                Symbol::Rule(other) => match index.get(other) {
+                                    Some(i) => Ok(Term::Rule(*i)),
+                                    None => Err(format!("Rule {} is not defined", other)),
+                                },
+                                Symbol::Literal(literal) => Ok(Term::Literal(literal.clone())),
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let start = *index
+            .get(&start)
+            .ok_or_else(|| format!("Rule {} is not defined", start))?;
+
+        let nullable_ids = rules.nullable();
+        let nullable = ids.iter().map(|id| nullable_ids.contains(id)).collect();
+        Ok(Self {
+            rules: compiled,
+            nullable,
+            start,
+        })
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        let chart = self.chart(message);
+        chart[message.len()].iter().any(|item| {
+            item.rule == self.start && item.origin == 0 && self.next_term(item).is_none()
+        })
+    }
+
+    /// Returns the length of the longest prefix of the message that some string matching the
+    /// start rule begins with, which shows where a message that doesn't match goes wrong.
+    pub fn longest_prefix(&self, message: &str) -> usize {
+        self.chart(message)
+            .iter()
+            .rposition(|items| !items.is_empty())
+            .unwrap_or(0)
+    }
+
+    fn next_term(&self, item: &Item) -> Option<&Term> {
+        self.rules[item.rule][item.alternative].get(item.dot)
+    }
+
+    /// Builds the Earley chart for a message: for each position, every item that can be
+    /// reached having matched the message up to there.
+    fn chart(&self, message: &str) -> Vec<Vec<Item>> {
+        let mut chart = Chart::new(message.len());
+        for alternative in 0..self.rules[self.start].len() {
+            chart.add(
+                0,
+                Item {
+                    rule: self.start,
+                    alternative,
+                    dot: 0,
+                    origin: 0,
+                },
+            );
+        }
+
+        for position in 0..=message.len() {
+            let mut i = 0;
+            while i < chart.items[position].len() {
+                let item = chart.items[position][i];
+                let advanced = Item {
+                    dot: item.dot + 1,
+                    ..item
+                };
+
+                match self.next_term(&item) {
+                    // Complete: every item waiting on this rule where it started moves past it.
+                    None => {
+                        let mut j = 0;
+                        while j < chart.items[item.origin].len() {
+                            let waiting = chart.items[item.origin][j];
+                            if self.next_term(&waiting) == Some(&Term::Rule(item.rule)) {
+                                chart.add(
+                                    position,
+                                    Item {
+                                        dot: waiting.dot + 1,
+                                        ..waiting
+                                    },
+                                );
+                            }
+                            j += 1;
+                        }
+                    }
+                    // Predict: the rule may start here. If it can match nothing, the item can
+                    // also move straight past it, since completing it here could come too late.
+                    Some(Term::Rule(rule)) => {
+                        for alternative in 0..self.rules[*rule].len() {
+                            chart.add(
+                                position,
+                                Item {
+                                    rule: *rule,
+                                    alternative,
+                                    dot: 0,
+                                    origin: position,
+                                },
+                            );
+                        }
+                        if self.nullable[*rule] {
+                            chart.add(position, advanced);
+                        }
+                    }
+                    // Scan: the literal moves the item to the position just past it.
+                    Some(Term::Literal(literal)) => {
+                        if message[position..].starts_with(literal.as_str()) {
+                            chart.add(position + literal.len(), advanced);
+                        }
+                    }
+                }
+                i += 1;
+            }
+        }
+
+        chart.items
+    }
+}
+
+/// The items at each position of a message, in the order they were found, without repeats.
+struct Chart {
+    items: Vec<Vec<Item>>,
+    seen: Vec<HashSet<Item>>,
+}
+
+impl Chart {
+    fn new(len: usize) -> Self {
+        Self {
+            items: vec![vec![]; len + 1],
+            seen: vec![HashSet::new(); len + 1],
+        }
+    }
+
+    fn add(&mut self, position: usize, item: Item) {
+        if self.seen[position].insert(item) {
+            self.items[position].push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matcher(rules: &[&str]) -> Matcher {
+        let mut result = Rules::new();
+        for rule in rules {
+            result.add_rule(rule).unwrap();
+        }
+        Matcher::new(&result, 0).unwrap()
+    }
+
+    #[test]
+    fn day_19_matches_loops_of_any_depth() {
+        let matcher = matcher(&[
+            "0: 8 11",
+            "8: 42 | 42 8",
+            "11: 42 31 | 42 11 31",
+            "42: \"a\"",
+            "31: \"b\"",
+        ]);
+
+        assert!(matcher.matches(&format!("{}{}", "a".repeat(20), "b".repeat(12))));
+        assert!(!matcher.matches(&format!("{}{}", "a".repeat(12), "b".repeat(12))));
+        assert!(!matcher.matches("ab"));
+    }
+
+    #[test]
+    fn day_19_matches_left_recursion() {
+        let matcher = matcher(&["0: 0 1 | 1", "1: \"a\""]);
+
+        assert!(matcher.matches("a"));
+        assert!(matcher.matches("aaaaaa"));
+        assert!(!matcher.matches(""));
+    }
+
+    #[test]
+    fn day_19_matches_empty_alternatives() {
+        let matcher = matcher(&["0: 1 2 1", "1: \"a\"", "2: 1 2 |"]);
+
+        assert!(matcher.matches("aa"));
+        assert!(matcher.matches("aaaa"));
+        assert!(!matcher.matches("a"));
+    }
+
+    #[test]
+    fn day_19_finds_longest_matching_prefix() {
+        let matcher = matcher(&["0: 1 1 2", "1: \"ab\" | \"c\"", "2: \"d\""]);
+
+        assert_eq!(4, matcher.longest_prefix("abcdd"));
+        assert_eq!(3, matcher.longest_prefix("cabx"));
+        assert_eq!(0, matcher.longest_prefix("x"));
+        assert_eq!(3, matcher.longest_prefix("ccd"));
+    }
+
+    #[test]
+    fn day_19_rejects_undefined_rules() {
+        let mut rules = Rules::new();
+        rules.add_rule("0: 1 2").unwrap();
+        rules.add_rule("1: \"a\"").unwrap();
+
+        assert_eq!(
+            Some(String::from("Rule 2 is not defined")),
+            Matcher::new(&rules, 0).err()
+        );
+        assert!(Matcher::new(&rules, 1).unwrap().matches("a"));
+    }
+}
@@ -0,0 +1,607 @@
+mod analysis;
+mod generator;
+mod matcher;
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Error as IoError, Write};
+use std::str::FromStr;
+
+use regex::Regex;
+
+use self::analysis::Analysis;
+use self::generator::{Generator, Rng};
+use self::matcher::Matcher;
+
+/// The replacements part two makes, which turn rules 8 and 11 into loops.
+const LOOPING_RULES: [&str; 2] = ["8: 42 | 42 8", "11: 42 31 | 42 11 31"];
+
+pub fn part_one(data: &[&str]) {
+    if let Ok((rules, messages)) = parse_data(data) {
+        print_count(count_matches(&rules, 0, messages));
+    }
+}
+
+pub fn part_two(data: &[&str]) {
+    if let Ok((mut rules, messages)) = parse_data(data) {
+        match rules.add_rules(&LOOPING_RULES) {
+            Ok(()) => print_count(count_matches(&rules, 0, messages)),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// How long the strings the analysis compares alternatives with can be.
+const AMBIGUITY_SEARCH_LENGTH: usize = 8;
+
+/// The most ways the start rule may match before `generate_strings` refuses to list its strings.
+const LIST_LIMIT: u128 = 100_000;
+
+/// Loads the rules and messages, replaces or adds the rules in `overrides`, and writes each
+/// message that matches the start rule, followed by the count. With `analyze`, it first reports
+/// problems with the rules, and shows how far each message that fails gets.
+pub fn match_messages(
+    data: &[&str],
+    overrides: &[&str],
+    start: usize,
+    analyze: bool,
+    out: &mut dyn Write,
+) -> Result<(), IoError> {
+    let (rules, messages) = match parse_data_with_overrides(data, overrides) {
+        Ok(parsed) => parsed,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+    if analyze {
+        write!(
+            out,
+            "{}",
+            Analysis::new(&rules, start, AMBIGUITY_SEARCH_LENGTH)
+        )?;
+    }
+    let matcher = match Matcher::new(&rules, start) {
+        Ok(matcher) => matcher,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+
+    let mut count = 0;
+    for message in messages {
+        if matcher.matches(message) {
+            writeln!(out, "{}", message)?;
+            count += 1;
+        } else if analyze {
+            match matcher.longest_prefix(message) {
+                prefix if prefix == message.len() => {
+                    writeln!(out, "{} does not match: it ends too soon", message)?
+                }
+                prefix => writeln!(
+                    out,
+                    "{} does not match: it goes wrong at column {}, after {:?}",
+                    message,
+                    prefix + 1,
+                    &message[..prefix]
+                )?,
+            }
+        }
+    }
+    writeln!(out, "Count of messages matching rule {}: {}", start, count)
+}
+
+/// What `generate_strings` writes.
+pub enum Generation {
+    /// The number of ways each rule matches strings, by length. A rule with overlapping
+    /// alternatives can match a string in more than one way, so this can be more than the number
+    /// of strings it matches.
+    Counts,
+    /// Every string the start rule matches.
+    All,
+    /// Random strings the start rule matches.
+    Sample { count: usize, seed: u64 },
+}
+
+/// Loads the rules, replaces or adds the rules in `overrides`, and writes strings up to
+/// `max_len` long that the rules match.
+pub fn generate_strings(
+    data: &[&str],
+    overrides: &[&str],
+    start: usize,
+    max_len: usize,
+    generation: Generation,
+    out: &mut dyn Write,
+) -> Result<(), IoError> {
+    let rules = match parse_data_with_overrides(data, overrides) {
+        Ok((rules, _)) => rules,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+    let generator = match Generator::new(&rules, max_len) {
+        Ok(generator) => generator,
+        Err(e) => return writeln!(out, "{}", e),
+    };
+    if generator.counts(start).is_none() {
+        return writeln!(out, "Rule {} is not defined", start);
+    }
+
+    match generation {
+        Generation::Counts => {
+            let mut ids: Vec<_> = rules.rules.keys().copied().collect();
+            ids.sort_unstable();
+            for id in ids {
+                let counts = generator.counts(id).unwrap();
+                let by_length: Vec<String> = counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(len, count)| format!("{}:{}", len, count))
+                    .collect();
+                writeln!(
+                    out,
+                    "Rule {} matches in {} ways ({})",
+                    id,
+                    generator.total(id).unwrap(),
+                    by_length.join(" ")
+                )?;
+            }
+        }
+        Generation::All => match generator.strings(start, LIST_LIMIT) {
+            Ok(strings) => {
+                for s in strings {
+                    writeln!(out, "{}", s)?;
+                }
+            }
+            Err(e) => writeln!(out, "{}", e)?,
+        },
+        Generation::Sample { count, seed } => {
+            let mut rng = Rng::new(seed);
+            for _ in 0..count {
+                match generator.sample(start, &mut rng) {
+                    Some(s) => writeln!(out, "{}", s)?,
+                    None => {
+                        return writeln!(
+                            out,
+                            "Rule {} matches no strings up to {} long",
+                            start, max_len
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts the messages matching the start rule, compiling the rules just once.
+fn count_matches(rules: &Rules, start: usize, messages: &[&str]) -> Result<usize, String> {
+    let matcher = Matcher::new(rules, start)?;
+    Ok(messages.iter().filter(|m| matcher.matches(m)).count())
+}
+
+fn print_count(count: Result<usize, String>) {
+    match count {
+        Ok(count) => println!("Count of valid messages: {}", count),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Splits the input into its rules and the messages after the blank line that ends them.
+fn parse_data<'a>(data: &'a [&'a str]) -> Result<(Rules, &'a [&'a str]), String> {
+    let end = data
+        .iter()
+        .position(|s| s.is_empty())
+        .ok_or("Rules must be followed by a blank line")?;
+
+    let mut rules = Rules::new();
+    rules.add_rules(&data[..end])?;
+
+    Ok((rules, &data[end + 1..]))
+}
+
+/// Parses the input like `parse_data`, then replaces or adds the rules in `overrides`.
+fn parse_data_with_overrides<'a>(
+    data: &'a [&'a str],
+    overrides: &[&str],
+) -> Result<(Rules, &'a [&'a str]), String> {
+    let (mut rules, messages) = parse_data(data)?;
+    rules
+        .add_rules(overrides)
+        .map_err(|e| format!("Invalid override: {}", e))?;
+    Ok((rules, messages))
+}
+
+lazy_static::lazy_static! {
+    static ref PARSE_RULE_REGEX: Regex = Regex::new(r"^(?P<id>\d+): *(?P<spec>.*)$").unwrap();
+}
+
+/// One piece of an alternative: a reference to another rule, or literal text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Symbol {
+    Rule(usize),
+    Literal(String),
+}
+
+/// The alternatives a rule can match, each a sequence of symbols. An empty alternative matches
+/// the empty string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RuleSpec {
+    alternatives: Vec<Vec<Symbol>>,
+}
+
+impl FromStr for RuleSpec {
+    type Err = String;
+
+    /// Parses alternatives separated by `|`, each made of rule numbers and quoted literals,
+    /// as in `1 "ab" 2 | "c"`.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        if spec.trim().is_empty() {
+            return Err("Could not parse rule specification".into());
+        }
+
+        let mut alternatives = vec![vec![]];
+        let mut chars = spec.char_indices().peekable();
+
+        while let Some((start, c)) = chars.next() {
+            match c {
+                '|' => alternatives.push(vec![]),
+                '"' => {
+                    let literal: String = chars
+                        .by_ref()
+                        .map(|(_, c)| c)
+                        .take_while(|c| *c != '"')
+                        .collect();
+                    if !spec[start + 1..].contains('"') {
+                        return Err(format!("Unterminated literal: {}", spec));
+                    }
+                    alternatives
+                        .last_mut()
+                        .unwrap()
+                        .push(Symbol::Literal(literal));
+                }
+                '0'..='9' => {
+                    let mut end = start + 1;
+                    while let Some((i, '0'..='9')) = chars.peek() {
+                        end = i + 1;
+                        chars.next();
+                    }
+                    let id = spec[start..end]
+                        .parse()
+                        .map_err(|_| format!("Invalid rule number: {}", &spec[start..end]))?;
+                    alternatives.last_mut().unwrap().push(Symbol::Rule(id));
+                }
+                c if c.is_whitespace() => {}
+                c => {
+                    return Err(format!(
+                        "Unexpected '{}' in rule specification: {}",
+                        c, spec
+                    ))
+                }
+            }
+        }
+
+        Ok(Self { alternatives })
+    }
+}
+
+struct Rules {
+    rules: HashMap<usize, RuleSpec>,
+}
+
+impl Rules {
+    fn new() -> Self {
+        Self {
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Lists every rule a rule refers to, in any alternative.
+    fn references(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rules[&id]
+            .alternatives
+            .iter()
+            .flatten()
+            .filter_map(|symbol| match symbol {
+                Symbol::Rule(other) => Some(*other),
+                Symbol::Literal(_) => None,
+            })
+    }
+
+    /// Finds the defined rules the start rule refers to, however indirectly, including itself.
+    fn reachable(&self, start: usize) -> HashSet<usize> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(rule) = stack.pop() {
+            if self.rules.contains_key(&rule) && reachable.insert(rule) {
+                stack.extend(self.references(rule));
+            }
+        }
+        reachable
+    }
+
+    /// Finds the rules that can match the empty string, by marking rules with an alternative
+    /// made only of nullable symbols until nothing changes. Undefined rules match nothing.
+    fn nullable(&self) -> HashSet<usize> {
+        let mut nullable = HashSet::new();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, spec) in &self.rules {
+                if nullable.contains(id) {
+                    continue;
+                }
+                let is_nullable = spec.alternatives.iter().any(|symbols| {
+                    symbols.iter().all(|symbol| match symbol {
+                        Symbol::Rule(other) => nullable.contains(other),
+                        Symbol::Literal(literal) => literal.is_empty(),
+                    })
+                });
+                if is_nullable {
+                    nullable.insert(*id);
+                    changed = true;
+                }
+            }
+        }
+
+        nullable
+    }
+
+    /// Compiles the start rule into a regular expression that matches whole messages. Regular
+    /// expressions can't recurse, so a rule that refers back to itself is unrolled `depth`
+    /// times, which misses messages that need it to repeat more often than that.
+    #[cfg(test)]
+    fn to_regex(&self, start: usize, depth: usize) -> Regex {
+        let pattern = self
+            .compile_rule(start, depth, &mut vec![])
+            // Matches nothing.
+            .unwrap_or_else(|| String::from("[^\\s\\S]"));
+
+        Regex::new(&format!("^(?:{})$", pattern)).unwrap()
+    }
+
+    /// Compiles a rule, leaving out the alternatives that would take it past its unrolling
+    /// depth, or that refer to undefined rules. Returns `None` if no alternatives are left.
+    #[cfg(test)]
+    fn compile_rule(&self, id: usize, depth: usize, stack: &mut Vec<usize>) -> Option<String> {
+        if stack.iter().filter(|i| **i == id).count() > depth {
+            return None;
+        }
+        let spec = self.rules.get(&id)?;
+
+        stack.push(id);
+        let alternatives: Vec<String> = spec
+            .alternatives
+            .iter()
+            .filter_map(|symbols| {
+                symbols
+                    .iter()
+                    .map(|symbol| match symbol {
+                        Symbol::Rule(other) => self.compile_rule(*other, depth, stack),
+                        Symbol::Literal(literal) => Some(regex::escape(literal)),
+                    })
+                    .collect()
+            })
+            .collect();
+        stack.pop();
+
+        match alternatives.len() {
+            0 => None,
+            1 => alternatives.into_iter().next(),
+            _ => Some(format!("(?:{})", alternatives.join("|"))),
+        }
+    }
+
+    /// Parses a rule and adds it, replacing any rule with the same number.
+    fn add_rule(&mut self, raw_rule: &str) -> Result<(), String> {
+        if let Some(caps) = PARSE_RULE_REGEX.captures(raw_rule) {
+            let id: usize = caps["id"]
+                .parse()
+                .map_err(|_| format!("Invalid rule number: {}", raw_rule))?;
+            let spec = &caps["spec"];
+
+            let spec: RuleSpec = spec.parse()?;
+            self.rules.insert(id, spec);
+
+            return Ok(());
+        }
+
+        Err(format!("Could not parse rule: {}", raw_rule))
+    }
+
+    /// Adds every rule in a list, skipping blank lines and `#` comments.
+    fn add_rules(&mut self, raw_rules: &[&str]) -> Result<(), String> {
+        raw_rules
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty() && !s.starts_with('#'))
+            .try_for_each(|s| self.add_rule(s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day_19_matches_simple_rule() -> Result<(), String> {
+        let mut rules = Rules::new();
+        rules.add_rule("0: \"a\"")?;
+        let message = "a";
+
+        assert!(Matcher::new(&rules, 0)?.matches(message));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_does_not_match_simple_rule() -> Result<(), String> {
+        let mut rules = Rules::new();
+        rules.add_rule("0: \"a\"")?;
+        let message = "bb";
+
+        assert!(!Matcher::new(&rules, 0)?.matches(message));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_matches_compound_rule() -> Result<(), String> {
+        let mut rules = Rules::new();
+        rules.add_rule("0: 1 2")?;
+        rules.add_rule("1: \"a\"")?;
+        rules.add_rule("2: \"b\"")?;
+        let message = "ab";
+
+        assert!(Matcher::new(&rules, 0)?.matches(message));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_matches_or_rule() -> Result<(), String> {
+        let mut rules = Rules::new();
+        rules.add_rule("0: 1 2 | 3 4")?;
+        rules.add_rule("1: \"a\"")?;
+        rules.add_rule("2: \"b\"")?;
+        rules.add_rule("3: \"c\"")?;
+        rules.add_rule("4: \"d\"")?;
+
+        assert!(Matcher::new(&rules, 0)?.matches("ab"));
+        assert!(Matcher::new(&rules, 0)?.matches("cd"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_counts_valid_messages() {
+        let data = vec![
+            "0: 4 1 5",
+            "1: 2 3 | 3 2",
+            "2: 4 4 | 5 5",
+            "3: 4 5 | 5 4",
+            "4: \"a\"",
+            "5: \"b\"",
+            "",
+            "ababbb",
+            "bababa",
+            "abbbab",
+            "aaabbb",
+            "aaaabbb",
+        ];
+
+        if let Ok((rules, messages)) = parse_data(&data) {
+            assert_eq!(Ok(2), count_matches(&rules, 0, messages));
+            let regex = rules.to_regex(0, 1);
+            assert_eq!(2, messages.iter().filter(|m| regex.is_match(m)).count());
+        }
+    }
+
+    #[test]
+    fn day_19_counts_valid_messages_with_looping_rules() {
+        let data = vec![
+            "42: 9 14 | 10 1",
+            "9: 14 27 | 1 26",
+            "10: 23 14 | 28 1",
+            "1: \"a\"",
+            "11: 42 31",
+            // "11: 42 31 | 42 11 31",
+            "5: 1 14 | 15 1",
+            "19: 14 1 | 14 14",
+            "12: 24 14 | 19 1",
+            "16: 15 1 | 14 14",
+            "31: 14 17 | 1 13",
+            "6: 14 14 | 1 14",
+            "2: 1 24 | 14 4",
+            "0: 8 11",
+            "13: 14 3 | 1 12",
+            "15: 1 | 14",
+            "17: 14 2 | 1 7",
+            "23: 25 1 | 22 14",
+            "28: 16 1",
+            "4: 1 1",
+            "20: 14 14 | 1 15",
+            "3: 5 14 | 16 1",
+            "27: 1 6 | 14 18",
+            "14: \"b\"",
+            "21: 14 1 | 1 14",
+            "25: 1 1 | 1 14",
+            "22: 14 14",
+            "8: 42",
+            // "8: 42 | 42 8",
+            "26: 14 22 | 1 20",
+            "18: 15 15",
+            "7: 14 5 | 1 21",
+            "24: 14 1",
+            "",
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa",
+            "bbabbbbaabaabba",
+            "babbbbaabbbbbabbbbbbaabaaabaaa",
+            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa",
+            "bbbbbbbaaaabbbbaaabbabaaa",
+            "bbbababbbbaaaaaaaabbababaaababaabab",
+            "ababaaaaaabaaab",
+            "ababaaaaabbbaba",
+            "baabbaaaabbaaaababbaababb",
+            "abbbbabbbbaaaababbbbbbaaaababb",
+            "aaaaabbaabaaaaababaa",
+            "aaaabbaaaabbaaa",
+            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa",
+            "babaaabbbaaabaababbaabababaaab",
+            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba",
+        ];
+
+        if let Ok((rules, messages)) = parse_data(&data) {
+            assert_eq!(Ok(3), count_matches(&rules, 0, messages));
+            let regex = rules.to_regex(0, 1);
+            assert_eq!(3, messages.iter().filter(|m| regex.is_match(m)).count());
+        }
+        if let Ok((mut rules, messages)) = parse_data(&data) {
+            rules.add_rules(&LOOPING_RULES).unwrap();
+            assert_eq!(Ok(12), count_matches(&rules, 0, messages));
+        }
+    }
+
+    #[test]
+    fn day_19_parses_general_alternatives() -> Result<(), String> {
+        let spec: RuleSpec = r#"1 "ab" | "c|d" 2 3 |"#.parse()?;
+
+        assert_eq!(
+            vec![
+                vec![Symbol::Rule(1), Symbol::Literal("ab".into())],
+                vec![
+                    Symbol::Literal("c|d".into()),
+                    Symbol::Rule(2),
+                    Symbol::Rule(3)
+                ],
+                vec![],
+            ],
+            spec.alternatives
+        );
+        assert!(r#"1 "ab"#.parse::<RuleSpec>().is_err());
+        assert!("1 x".parse::<RuleSpec>().is_err());
+        assert!("".parse::<RuleSpec>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn day_19_matches_any_start_rule_with_overrides() -> Result<(), String> {
+        let data = [
+            "0: 1 | 2 | 3",
+            "1: \"ab\"",
+            "2: \"cd\" 1",
+            "3: \"e\"",
+            "",
+            "ab",
+            "cdab",
+            "e",
+            "cdcdab",
+        ];
+        let (mut rules, messages) = parse_data(&data)?;
+
+        assert_eq!(Ok(3), count_matches(&rules, 0, messages));
+        assert_eq!(Ok(1), count_matches(&rules, 2, messages));
+        rules.add_rules(&["# Let rule 2 repeat.", "2: \"cd\" 1 | \"cd\" 2"])?;
+        assert_eq!(Ok(4), count_matches(&rules, 0, messages));
+        let regex = rules.to_regex(0, 1);
+        assert_eq!(4, messages.iter().filter(|m| regex.is_match(m)).count());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 mod util;
+pub use util::automaton;
 pub use util::grid;
+pub use util::life_rule;
+pub use util::render;
+pub use util::simulation;
 
 mod day_01;
 mod day_02;
@@ -22,7 +26,15 @@ mod day_18;
 mod day_19;
 mod day_20;
 
-use lazy_static;
+pub use day_07::describe_bag;
+pub use day_08::{analyze, compile, debug, decompile, is_compiled, list};
+pub use day_14::dump_memory;
+pub use day_16::{export_tickets, list_assignments, validate_tickets};
+pub use day_18::calculate;
+pub use day_19::{generate_strings, match_messages, Generation};
+
+use std::io::Error as IoError;
+use std::path::Path;
 
 lazy_static::lazy_static! {
     static ref BUILDERS: Vec<DayRunner> = vec![
@@ -51,11 +63,15 @@ lazy_static::lazy_static! {
 
 type DayRunner = (fn(&[&str]), fn(&[&str]));
 
+/// Runs a day's simulations for a number of generations, saving a frame of each generation to
+/// the given directory.
+type FrameDumper = fn(&[&str], &Path, usize) -> Result<(), IoError>;
+
 pub fn run(runner: DayRunner, data: &[&str]) {
     println!("\nPart One\n========");
-    (runner.0)(&data);
+    (runner.0)(data);
     println!("\nPart Two\n========");
-    (runner.1)(&data);
+    (runner.1)(data);
 }
 
 pub fn days_implemented() -> u8 {
@@ -65,3 +81,11 @@ pub fn days_implemented() -> u8 {
 pub fn get_runner(day: u8) -> DayRunner {
     BUILDERS[(day - 1) as usize]
 }
+
+pub fn get_frame_dumper(day: u8) -> Option<FrameDumper> {
+    match day {
+        11 => Some(day_11::dump_frames),
+        17 => Some(day_17::dump_frames),
+        _ => None,
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use super::simulation::Simulation;
+
+/// A point in an `N`-dimensional lattice.
+pub type Coord<const N: usize> = [i64; N];
+
+/// Decides the fate of a single cell from one generation to the next.
+pub trait Rule {
+    /// Returns whether a cell will be active in the next generation, given whether it's active
+    /// now and how many of its neighbors are active.
+    fn next_state(&self, active: bool, active_neighbors: usize) -> bool;
+
+    /// Returns the number of states a cell can be in. Rules with more than two states let a cell
+    /// that stops being active decay through the extra states, during which it neither counts
+    /// as an active neighbor nor can become active again.
+    fn states(&self) -> u8 {
+        2
+    }
+}
+
+/// Describes which cells are neighbors of each other.
+pub enum Neighborhood<const N: usize> {
+    /// The lattice is unbounded, and each cell's neighbors are the cells offset from it by
+    /// these deltas.
+    Moore(Vec<Coord<N>>),
+    /// Only the listed cells exist, and each cell's neighbors are listed alongside it.
+    Graph(HashMap<Coord<N>, Vec<Coord<N>>>),
+}
+
+impl<const N: usize> Neighborhood<N> {
+    /// Creates an unbounded neighborhood in which every cell touching another, even at a
+    /// corner, is its neighbor.
+    pub fn moore() -> Self {
+        Neighborhood::Moore(neighbor_deltas())
+    }
+
+    fn neighbors(&self, coord: &Coord<N>) -> Vec<Coord<N>> {
+        match self {
+            Neighborhood::Moore(deltas) => deltas.iter().map(|d| offset(coord, d)).collect(),
+            Neighborhood::Graph(edges) => edges.get(coord).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// Generates the offsets from a cell to each of its `3^N - 1` Moore neighbors.
+pub fn neighbor_deltas<const N: usize>() -> Vec<Coord<N>> {
+    let count = 3usize.pow(N as u32);
+
+    (0..count)
+        .map(|mut index| {
+            let mut delta = [0; N];
+            for d in delta.iter_mut() {
+                *d = (index % 3) as i64 - 1;
+                index /= 3;
+            }
+            delta
+        })
+        .filter(|delta| delta.iter().any(|d| *d != 0))
+        .collect()
+}
+
+fn offset<const N: usize>(coord: &Coord<N>, delta: &Coord<N>) -> Coord<N> {
+    let mut result = *coord;
+    for (r, d) in result.iter_mut().zip(delta.iter()) {
+        *r += d;
+    }
+    result
+}
+
+/// The smallest box containing a set of cells, with inclusive corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds<const N: usize> {
+    pub min: Coord<N>,
+    pub max: Coord<N>,
+}
+
+impl<const N: usize> Bounds<N> {
+    /// Finds the bounds of the given cells, if there are any.
+    pub fn of<'a>(coords: impl IntoIterator<Item = &'a Coord<N>>) -> Option<Self> {
+        coords.into_iter().fold(None, |bounds, coord| {
+            Some(match bounds {
+                None => Self {
+                    min: *coord,
+                    max: *coord,
+                },
+                Some(bounds) => bounds.including(coord),
+            })
+        })
+    }
+
+    /// Returns these bounds grown just enough to contain the given cell.
+    pub fn including(&self, coord: &Coord<N>) -> Self {
+        let mut result = *self;
+        for (d, value) in coord.iter().enumerate() {
+            result.min[d] = result.min[d].min(*value);
+            result.max[d] = result.max[d].max(*value);
+        }
+        result
+    }
+
+    /// Returns the number of cells spanned along the given dimension.
+    pub fn extent(&self, dimension: usize) -> usize {
+        (self.max[dimension] - self.min[dimension] + 1) as usize
+    }
+}
+
+/// A cellular automaton stored sparsely as the set of active cells, along with the states of any
+/// cells that are decaying.
+pub struct Automaton<R: Rule, const N: usize> {
+    rule: R,
+    neighborhood: Neighborhood<N>,
+    active: HashSet<Coord<N>>,
+    decaying: HashMap<Coord<N>, u8>,
+}
+
+impl<R: Rule, const N: usize> Automaton<R, N> {
+    /// Creates a new `Automaton` with the given cells active.
+    pub fn new(
+        rule: R,
+        neighborhood: Neighborhood<N>,
+        active: impl IntoIterator<Item = Coord<N>>,
+    ) -> Self {
+        Self {
+            rule,
+            neighborhood,
+            active: active.into_iter().collect(),
+            decaying: HashMap::new(),
+        }
+    }
+
+    /// Advances the automaton by one generation.
+    ///
+    /// In an unbounded neighborhood, only the active cells and their neighbors are considered,
+    /// so a rule that activates cells with no active neighbors won't fill empty space.
+    pub fn tick(&mut self) {
+        let candidates: HashSet<Coord<N>> = match &self.neighborhood {
+            Neighborhood::Moore(_) => self
+                .active
+                .iter()
+                .flat_map(|coord| self.neighborhood.neighbors(coord))
+                .chain(self.active.iter().copied())
+                .collect(),
+            Neighborhood::Graph(edges) => edges.keys().copied().collect(),
+        };
+
+        let active: HashSet<Coord<N>> = candidates
+            .into_iter()
+            .filter(|candidate| !self.decaying.contains_key(candidate))
+            .filter(|candidate| {
+                self.rule
+                    .next_state(self.is_active(candidate), self.active_neighbors(candidate))
+            })
+            .collect();
+
+        let states = self.rule.states();
+        let mut decaying: HashMap<Coord<N>, u8> = self
+            .decaying
+            .iter()
+            .filter(|(_, state)| **state + 1 < states)
+            .map(|(coord, state)| (*coord, state + 1))
+            .collect();
+        if states > 2 {
+            decaying.extend(self.active.difference(&active).map(|coord| (*coord, 2)));
+        }
+
+        self.active = active;
+        self.decaying = decaying;
+    }
+
+    /// Returns the number of active neighbors of the cell at the given coordinates.
+    pub fn active_neighbors(&self, coord: &Coord<N>) -> usize {
+        self.neighborhood
+            .neighbors(coord)
+            .iter()
+            .filter(|c| self.active.contains(*c))
+            .count()
+    }
+
+    /// Returns whether the cell at the given coordinates is active.
+    pub fn is_active(&self, coord: &Coord<N>) -> bool {
+        self.active.contains(coord)
+    }
+
+    /// Returns the set of active cells.
+    pub fn active(&self) -> &HashSet<Coord<N>> {
+        &self.active
+    }
+
+    /// Returns the number of active cells.
+    #[cfg(test)]
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Lists every cell that isn't inactive along with its state, where 1 means active and
+    /// higher numbers are the decaying states.
+    pub fn cells(&self) -> Vec<(Coord<N>, u8)> {
+        self.active
+            .iter()
+            .map(|coord| (*coord, 1))
+            .chain(self.decaying.iter().map(|(coord, state)| (*coord, *state)))
+            .collect()
+    }
+
+    /// Returns the number of states a cell can be in.
+    pub fn states(&self) -> u8 {
+        self.rule.states()
+    }
+}
+
+impl<R: Rule, const N: usize> Simulation for Automaton<R, N> {
+    type State = (Vec<Coord<N>>, Vec<(Coord<N>, u8)>);
+
+    fn state(&self) -> Self::State {
+        let mut active: Vec<_> = self.active.iter().copied().collect();
+        active.sort_unstable();
+        let mut decaying: Vec<_> = self.decaying.iter().map(|(c, s)| (*c, *s)).collect();
+        decaying.sort_unstable();
+
+        (active, decaying)
+    }
+
+    fn step(&mut self) {
+        self.tick();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::days::life_rule::LifeRule;
+    use crate::days::simulation::{simulate, Outcome};
+
+    struct Conway;
+
+    impl Rule for Conway {
+        fn next_state(&self, active: bool, active_neighbors: usize) -> bool {
+            active_neighbors == 3 || (active && active_neighbors == 2)
+        }
+    }
+
+    #[test]
+    fn automaton_generates_neighbor_deltas_for_each_dimension() {
+        assert_eq!(8, neighbor_deltas::<2>().len());
+        assert_eq!(26, neighbor_deltas::<3>().len());
+        assert_eq!(80, neighbor_deltas::<4>().len());
+        assert_eq!(242, neighbor_deltas::<5>().len());
+        assert_eq!(728, neighbor_deltas::<6>().len());
+    }
+
+    #[test]
+    fn automaton_neighbor_deltas_are_unique_unit_offsets() {
+        let deltas = neighbor_deltas::<3>();
+        let unique: HashSet<Coord<3>> = deltas.iter().copied().collect();
+
+        assert_eq!(deltas.len(), unique.len());
+        assert!(!unique.contains(&[0, 0, 0]));
+        assert!(deltas.iter().flatten().all(|d| (-1..=1).contains(d)));
+    }
+
+    #[test]
+    fn automaton_oscillates_blinker() {
+        let mut automaton =
+            Automaton::new(Conway, Neighborhood::moore(), vec![[0, -1], [0, 0], [0, 1]]);
+        let horizontal = automaton.active().clone();
+
+        automaton.tick();
+        let vertical: HashSet<Coord<2>> = vec![[-1, 0], [0, 0], [1, 0]].into_iter().collect();
+        assert_eq!(&vertical, automaton.active());
+
+        automaton.tick();
+        assert_eq!(&horizontal, automaton.active());
+    }
+
+    #[test]
+    fn automaton_simulation_detects_blinker_period() {
+        let mut automaton =
+            Automaton::new(Conway, Neighborhood::moore(), vec![[0, -1], [0, 0], [0, 1]]);
+
+        assert_eq!(
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            },
+            simulate(&mut automaton, 10)
+        );
+    }
+
+    #[test]
+    fn automaton_runs_in_six_dimensions() {
+        let mut automaton = Automaton::new(
+            Conway,
+            Neighborhood::<6>::moore(),
+            vec![[0, 0, 0, 0, 0, 0], [1, 0, 0, 0, 0, 0], [2, 0, 0, 0, 0, 0]],
+        );
+
+        assert_eq!(2, automaton.active_neighbors(&[1, 0, 0, 0, 0, 0]));
+
+        automaton.tick();
+
+        assert!(automaton.is_active(&[1, 0, 0, 0, 0, 0]));
+        assert!(!automaton.is_active(&[0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn automaton_keeps_decaying_cells_from_becoming_active() {
+        let start = vec![[0, 0], [0, 1], [0, 2]];
+        let two_states: LifeRule = "B2/S".parse().unwrap();
+        let three_states: LifeRule = "B2/S/C3".parse().unwrap();
+        let mut plain = Automaton::new(two_states, Neighborhood::moore(), start.clone());
+        let mut decaying = Automaton::new(three_states, Neighborhood::moore(), start);
+
+        for _ in 0..2 {
+            plain.tick();
+            decaying.tick();
+        }
+
+        assert!(plain.is_active(&[0, 0]));
+        assert!(!decaying.is_active(&[0, 0]));
+    }
+
+    #[test]
+    fn automaton_only_considers_cells_in_graph() {
+        let mut edges: HashMap<Coord<1>, Vec<Coord<1>>> = HashMap::new();
+        edges.insert([0], vec![[1]]);
+        edges.insert([1], vec![[0]]);
+        let mut automaton = Automaton::new(Conway, Neighborhood::Graph(edges), vec![[0], [1]]);
+
+        automaton.tick();
+
+        assert_eq!(0, automaton.active_count());
+    }
+}
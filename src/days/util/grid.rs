@@ -1,7 +1,7 @@
 use std::fmt::{Display, Error as FmtError, Formatter};
 
 /// Kind of a cheat. I copied this code from one of my other projects.
-#[derive(Clone, PartialEq, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct Grid {
     rows: u32,
     columns: u32,
@@ -29,7 +29,7 @@ impl Grid {
     /// let grid = Grid::new(10, 10, cells);
     /// ```
     pub fn new(rows: u32, columns: u32, cells: Vec<char>) -> Result<Self, &'static str> {
-        if (rows as u32 * columns as u32) as usize != cells.len() {
+        if (rows * columns) as usize != cells.len() {
             return Err("Number of rows and columns doesn't fit supplied collection of cells");
         }
 
@@ -40,10 +40,8 @@ impl Grid {
         })
     }
 
-    /// Lists the values of the neighbors of the cell at the given coordinates.
-    pub fn neighbors(&self, row: u32, column: u32) -> Vec<char> {
-        let mut result = vec![];
-
+    /// Lists the coordinates of the neighbors of the cell at the given coordinates.
+    pub fn neighbors(&self, row: u32, column: u32) -> Vec<(u32, u32)> {
         NEIGHBOR_DELTAS
             .iter()
             .filter_map(|(r, c)| {
@@ -55,19 +53,13 @@ impl Grid {
                     Some((r as u32, c as u32))
                 }
             })
-            .for_each(|(r, c)| {
-                if let Some(index) = self.linear_index(r, c) {
-                    result.push(self.cells[index].clone());
-                }
-            });
-
-        result
+            .filter(|(r, c)| self.linear_index(*r, *c).is_some())
+            .collect()
     }
 
-    /// Lists the values of the nearest visible neighbors of the cell at the given coordinates.
-    pub fn visible_neighbors(&self, start_row: u32, start_column: u32) -> Vec<char> {
-        let mut result = vec![];
-
+    /// Lists the coordinates of the nearest visible neighbors of the cell at the given
+    /// coordinates.
+    pub fn visible_neighbors(&self, start_row: u32, start_column: u32) -> Vec<(u32, u32)> {
         NEIGHBOR_DELTAS
             .iter()
             .filter_map(|(r, c)| {
@@ -88,13 +80,7 @@ impl Grid {
                 }
                 None
             })
-            .for_each(|(r, c)| {
-                if let Some(index) = self.linear_index(r, c) {
-                    result.push(self.cells[index].clone());
-                }
-            });
-
-        result
+            .collect()
     }
 
     fn linear_index(&self, row: u32, column: u32) -> Option<usize> {
@@ -102,19 +88,19 @@ impl Grid {
             return None;
         }
 
-        let index = self.columns as u32 * row as u32 + column as u32;
+        let index = self.columns * row + column;
         Some(index as usize)
     }
 
     /// Returns the value of the cell at the given coordinates.
     pub fn get(&self, row: u32, column: u32) -> char {
-        self.cells[self.linear_index(row, column).unwrap()].clone()
+        self.cells[self.linear_index(row, column).unwrap()]
     }
 
     /// Changes the value of the cell at the given coordinates.
     pub fn set(&mut self, row: u32, column: u32, value: char) {
         if let Some(index) = self.linear_index(row, column) {
-            self.cells[index] = value.clone();
+            self.cells[index] = value;
         }
     }
 
@@ -139,7 +125,7 @@ impl Display for Grid {
             for cell in line {
                 write!(f, "{}", cell)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -0,0 +1,222 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use super::automaton::Rule;
+
+/// A Life-like rule written in B/S notation, such as `B3/S23` for Conway's Game of Life.
+///
+/// Each of the `B` (birth) and `S` (survival) parts lists the numbers of active neighbors that
+/// activate an inactive cell or keep an active cell active. Single digits can be run together,
+/// as in `S23`; counts above nine need commas or ranges, as in `B3,10/S2-4`.
+///
+/// An optional `C` part, as in `B2/S/C3`, gives the total number of states for a Generations
+/// rule. A cell that fails to survive then decays through the extra states before becoming
+/// inactive again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeRule {
+    birth: HashSet<usize>,
+    survival: HashSet<usize>,
+    states: u8,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidSyntax,
+    CountNotANumber,
+    TooFewStates,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{}",
+            match self {
+                ParseError::InvalidSyntax => "Rule doesn't follow B/S notation",
+                ParseError::CountNotANumber => "Neighbor count isn't a number or range",
+                ParseError::TooFewStates => "State count must be a number of at least 2",
+            }
+        )
+    }
+}
+
+impl FromStr for LifeRule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+
+        for part in s.trim().split('/') {
+            let mut chars = part.chars();
+            let prefix = chars.next().map(|c| c.to_ascii_uppercase());
+            let counts = chars.as_str();
+            match prefix {
+                Some('B') if birth.is_none() => birth = Some(parse_counts(counts)?),
+                Some('S') if survival.is_none() => survival = Some(parse_counts(counts)?),
+                Some('C') | Some('G') if states.is_none() => {
+                    let count: u8 = counts.parse().map_err(|_| ParseError::TooFewStates)?;
+                    if count < 2 {
+                        return Err(ParseError::TooFewStates);
+                    }
+                    states = Some(count);
+                }
+                _ => return Err(ParseError::InvalidSyntax),
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self {
+                birth,
+                survival,
+                states: states.unwrap_or(2),
+            }),
+            _ => Err(ParseError::InvalidSyntax),
+        }
+    }
+}
+
+fn parse_counts(counts: &str) -> Result<HashSet<usize>, ParseError> {
+    if !counts.contains(',') && !counts.contains('-') {
+        return counts
+            .chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .map(|d| d as usize)
+                    .ok_or(ParseError::CountNotANumber)
+            })
+            .collect();
+    }
+
+    let mut result = HashSet::new();
+    for item in counts.split(',') {
+        let bounds: Vec<&str> = item.split('-').collect();
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .map_err(|_| ParseError::CountNotANumber)
+        };
+        match bounds[..] {
+            [n] => {
+                result.insert(parse(n)?);
+            }
+            [low, high] => result.extend(parse(low)?..=parse(high)?),
+            _ => return Err(ParseError::CountNotANumber),
+        }
+    }
+
+    Ok(result)
+}
+
+impl Display for LifeRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "B{}/S{}",
+            format_counts(&self.birth),
+            format_counts(&self.survival)
+        )?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn format_counts(counts: &HashSet<usize>) -> String {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort();
+
+    if counts.iter().all(|c| **c < 10) {
+        counts.iter().map(|c| c.to_string()).collect()
+    } else {
+        counts
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Rule for LifeRule {
+    fn next_state(&self, active: bool, active_neighbors: usize) -> bool {
+        match active {
+            true => self.survival.contains(&active_neighbors),
+            false => self.birth.contains(&active_neighbors),
+        }
+    }
+
+    fn states(&self) -> u8 {
+        self.states
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn life_rule_parses_conway() -> Result<(), ParseError> {
+        let rule: LifeRule = "B3/S23".parse()?;
+
+        assert!(rule.next_state(false, 3));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2));
+        assert!(rule.next_state(true, 3));
+        assert!(!rule.next_state(true, 4));
+        assert_eq!(2, rule.states());
+
+        Ok(())
+    }
+
+    #[test]
+    fn life_rule_parses_lists_and_ranges() -> Result<(), ParseError> {
+        let rule: LifeRule = "b3,10/s2-4".parse()?;
+
+        assert!(rule.next_state(false, 10));
+        assert!(rule.next_state(true, 4));
+        assert!(!rule.next_state(true, 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn life_rule_parses_generations() -> Result<(), ParseError> {
+        let rule: LifeRule = "B2/S/C3".parse()?;
+
+        assert!(rule.next_state(false, 2));
+        assert!(!rule.next_state(true, 2));
+        assert_eq!(3, rule.states());
+
+        Ok(())
+    }
+
+    #[test]
+    fn life_rule_rejects_invalid_syntax() {
+        assert_eq!(Err(ParseError::InvalidSyntax), "B3".parse::<LifeRule>());
+        assert_eq!(
+            Err(ParseError::InvalidSyntax),
+            "B3/S23/X".parse::<LifeRule>()
+        );
+        assert_eq!(
+            Err(ParseError::CountNotANumber),
+            "B3/S2a".parse::<LifeRule>()
+        );
+        assert_eq!(
+            Err(ParseError::TooFewStates),
+            "B3/S23/C1".parse::<LifeRule>()
+        );
+    }
+
+    #[test]
+    fn life_rule_round_trips_through_display() -> Result<(), ParseError> {
+        assert_eq!("B3/S23", "B3/S23".parse::<LifeRule>()?.to_string());
+        assert_eq!("B2/S/C3", "B2/S/C3".parse::<LifeRule>()?.to_string());
+        assert_eq!("B3,10/S234", "B3,10/S2-4".parse::<LifeRule>()?.to_string());
+
+        Ok(())
+    }
+}
@@ -1 +1,5 @@
+pub mod automaton;
 pub mod grid;
+pub mod life_rule;
+pub mod render;
+pub mod simulation;
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error as IoError, Write};
+use std::path::Path;
+
+use super::automaton::{Automaton, Bounds, Coord, Rule};
+
+const AXIS_NAMES: [&str; 4] = ["x", "y", "z", "w"];
+
+const BACKGROUND: [u8; 3] = [0, 0, 0];
+const SEPARATOR: [u8; 3] = [48, 48, 48];
+
+/// An image of a single generation of a simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Frame {
+    /// Creates a new `Frame` with every pixel black.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    /// Changes the color of the pixel at the given coordinates.
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Returns whether every pixel is a shade of gray.
+    pub fn is_grayscale(&self) -> bool {
+        self.pixels.iter().all(|[r, g, b]| r == g && g == b)
+    }
+
+    /// Returns the file extension for the format `write()` uses.
+    pub fn extension(&self) -> &'static str {
+        match self.is_grayscale() {
+            true => "pgm",
+            false => "ppm",
+        }
+    }
+
+    /// Writes the frame as a binary PGM image if it's grayscale, or a binary PPM image if not.
+    pub fn write(&self, out: &mut impl Write) -> Result<(), IoError> {
+        if self.is_grayscale() {
+            write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+            let bytes: Vec<u8> = self.pixels.iter().map(|[v, _, _]| *v).collect();
+            out.write_all(&bytes)
+        } else {
+            write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+            let bytes: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+            out.write_all(&bytes)
+        }
+    }
+}
+
+/// Writes each frame to `dir` as `{prefix}_{generation}`, along with a text rendering when one
+/// is given.
+pub fn save_frames(
+    dir: &Path,
+    prefix: &str,
+    frames: &[(Frame, Option<String>)],
+) -> Result<(), IoError> {
+    fs::create_dir_all(dir)?;
+
+    for (generation, (frame, text)) in frames.iter().enumerate() {
+        let name = format!("{}_{:03}", prefix, generation);
+        let mut file = fs::File::create(dir.join(format!("{}.{}", name, frame.extension())))?;
+        frame.write(&mut file)?;
+        if let Some(text) = text {
+            fs::write(dir.join(format!("{}.txt", name)), text)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the coordinates beyond `x` and `y` of every slice within the bounds, with the last
+/// dimension varying slowest.
+fn slice_keys<const N: usize>(bounds: &Bounds<N>) -> Vec<Vec<i64>> {
+    let mut keys = vec![vec![]];
+    for d in 2..N {
+        keys = (bounds.min[d]..=bounds.max[d])
+            .flat_map(|value| {
+                keys.iter().map(move |key| {
+                    let mut key = key.clone();
+                    key.push(value);
+                    key
+                })
+            })
+            .collect();
+    }
+    keys
+}
+
+/// Renders the cells as a series of two-dimensional slices in the puzzle's format, with `x`
+/// increasing to the right and `y` increasing upward. Each slice is labeled with its remaining
+/// coordinates, such as `z=0, w=-1`.
+pub fn render_slices<const N: usize>(cells: &[(Coord<N>, u8)], bounds: &Bounds<N>) -> String {
+    let states: HashMap<Coord<N>, u8> = cells.iter().copied().collect();
+    let mut result = String::new();
+
+    for key in slice_keys(bounds) {
+        if !key.is_empty() {
+            let label: Vec<String> = key
+                .iter()
+                .enumerate()
+                .map(|(i, value)| format!("{}={}", axis_name(i + 2), value))
+                .collect();
+            result.push_str(&label.join(", "));
+            result.push('\n');
+        }
+
+        for y in (bounds.min[1]..=bounds.max[1]).rev() {
+            for x in bounds.min[0]..=bounds.max[0] {
+                let mut coord = [0; N];
+                coord[0] = x;
+                coord[1] = y;
+                coord[2..].copy_from_slice(&key);
+                result.push(match states.get(&coord) {
+                    Some(1) => '#',
+                    _ => '.',
+                });
+            }
+            result.push('\n');
+        }
+        result.push('\n');
+    }
+
+    result
+}
+
+fn axis_name(dimension: usize) -> String {
+    AXIS_NAMES
+        .get(dimension)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("d{}", dimension))
+}
+
+/// Draws the cells as a frame of slices laid out in a grid, one column per `z` and one row per
+/// combination of the remaining coordinates. Active cells are white, and decaying cells fade
+/// toward black as they age.
+pub fn draw_slices<const N: usize>(
+    cells: &[(Coord<N>, u8)],
+    bounds: &Bounds<N>,
+    states: u8,
+) -> Frame {
+    let tile_width = bounds.extent(0);
+    let tile_height = bounds.extent(1);
+    let columns = if N > 2 { bounds.extent(2) } else { 1 };
+    let rows: usize = (3..N).map(|d| bounds.extent(d)).product();
+
+    let width = columns * (tile_width + 1) - 1;
+    let height = rows * (tile_height + 1) - 1;
+    let mut frame = Frame::new(width, height);
+
+    for x in 0..width {
+        for y in 0..height {
+            if x % (tile_width + 1) == tile_width || y % (tile_height + 1) == tile_height {
+                frame.set(x, y, SEPARATOR);
+            }
+        }
+    }
+
+    for (coord, state) in cells {
+        let column = if N > 2 {
+            (coord[2] - bounds.min[2]) as usize
+        } else {
+            0
+        };
+        let mut row = 0;
+        for d in (3..N).rev() {
+            row = row * bounds.extent(d) + (coord[d] - bounds.min[d]) as usize;
+        }
+
+        let x = column * (tile_width + 1) + (coord[0] - bounds.min[0]) as usize;
+        let y = row * (tile_height + 1) + (bounds.max[1] - coord[1]) as usize;
+        let level = (255 * (states - state) as usize / (states - 1) as usize) as u8;
+        frame.set(x, y, [level; 3]);
+    }
+
+    frame
+}
+
+/// Runs the automaton for the given number of generations, drawing and rendering every
+/// generation including the first. All frames share the bounds of every generation combined, so
+/// they line up when played back in sequence.
+pub fn animate<R: Rule, const N: usize>(
+    automaton: &mut Automaton<R, N>,
+    generations: usize,
+) -> Vec<(Frame, Option<String>)> {
+    let mut history = vec![automaton.cells()];
+    for _ in 0..generations {
+        automaton.tick();
+        history.push(automaton.cells());
+    }
+
+    let bounds = Bounds::of(history.iter().flatten().map(|(coord, _)| coord)).unwrap_or(Bounds {
+        min: [0; N],
+        max: [0; N],
+    });
+
+    history
+        .iter()
+        .map(|cells| {
+            (
+                draw_slices(cells, &bounds, automaton.states()),
+                Some(render_slices(cells, &bounds)),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_labels_slices_in_puzzle_format() {
+        let cells = vec![([0, 1, 0], 1), ([1, 0, 0], 1), ([-1, -1, 1], 1)];
+        let bounds = Bounds::of(cells.iter().map(|(c, _)| c)).unwrap();
+
+        assert_eq!(
+            "z=0\n.#.\n..#\n...\n\nz=1\n...\n...\n#..\n\n",
+            render_slices(&cells, &bounds)
+        );
+    }
+
+    #[test]
+    fn render_labels_four_dimensional_slices() {
+        let cells = vec![([0, 0, 0, 0], 1), ([0, 0, 0, 1], 1)];
+        let bounds = Bounds::of(cells.iter().map(|(c, _)| c)).unwrap();
+
+        assert_eq!(
+            "z=0, w=0\n#\n\nz=0, w=1\n#\n\n",
+            render_slices(&cells, &bounds)
+        );
+    }
+
+    #[test]
+    fn render_draws_grayscale_slices_side_by_side() {
+        let cells = vec![([0, 0, 0], 1), ([1, 0, 1], 2)];
+        let bounds = Bounds::of(cells.iter().map(|(c, _)| c)).unwrap();
+
+        let frame = draw_slices(&cells, &bounds, 3);
+
+        assert_eq!(5, frame.width);
+        assert_eq!(1, frame.height);
+        assert_eq!(
+            vec![[255; 3], [0; 3], SEPARATOR, [0; 3], [127; 3]],
+            frame.pixels
+        );
+        assert_eq!("pgm", frame.extension());
+    }
+
+    #[test]
+    fn render_writes_pgm_and_ppm_headers() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(0, 0, [255; 3]);
+        let mut gray = vec![];
+        frame.write(&mut gray).unwrap();
+
+        frame.set(1, 0, [255, 0, 0]);
+        let mut color = vec![];
+        frame.write(&mut color).unwrap();
+
+        assert_eq!(b"P5\n2 1\n255\n\xff\x00".to_vec(), gray);
+        assert_eq!(b"P6\n2 1\n255\n\xff\xff\xff\xff\x00\x00".to_vec(), color);
+    }
+}
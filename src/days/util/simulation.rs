@@ -0,0 +1,132 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Something that evolves one step at a time from one state to the next.
+pub trait Simulation {
+    /// A snapshot of the simulation. Two snapshots must be equal exactly when the simulation
+    /// would evolve identically from them.
+    type State: Eq + Hash;
+
+    /// Takes a snapshot of the current state.
+    fn state(&self) -> Self::State;
+
+    /// Advances the simulation by one step.
+    fn step(&mut self);
+}
+
+/// How a simulation behaved when driven by `simulate()`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The state stopped changing from the given step onward.
+    FixedPoint { step: usize },
+    /// The state at the `start` step repeats every `period` steps.
+    Cycle { start: usize, period: usize },
+    /// No state repeated within the given number of steps.
+    Unsettled { steps: usize },
+}
+
+/// Steps the simulation until a state repeats or `max_steps` steps have passed.
+///
+/// Each state is recorded along with a fingerprint of it. A fingerprint collision only marks
+/// the earlier state as a candidate; a repeat is reported only when the states are equal, so
+/// hash collisions can't end the simulation early.
+pub fn simulate<S: Simulation>(simulation: &mut S, max_steps: usize) -> Outcome {
+    let mut history: Vec<S::State> = vec![];
+    let mut fingerprints: HashMap<u64, Vec<usize>> = HashMap::new();
+
+    for step in 0..=max_steps {
+        let state = simulation.state();
+        let candidates = fingerprints.entry(fingerprint(&state)).or_default();
+
+        if let Some(&start) = candidates.iter().find(|&&i| history[i] == state) {
+            return match step - start {
+                1 => Outcome::FixedPoint { step: start },
+                period => Outcome::Cycle { start, period },
+            };
+        }
+
+        candidates.push(step);
+        history.push(state);
+
+        if step < max_steps {
+            simulation.step();
+        }
+    }
+
+    Outcome::Unsettled { steps: max_steps }
+}
+
+fn fingerprint<T: Hash>(t: &T) -> u64 {
+    let mut s = DefaultHasher::new();
+    t.hash(&mut s);
+    s.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Counts up to `limit`, then wraps around to `restart`.
+    struct Counter {
+        value: u32,
+        limit: u32,
+        restart: u32,
+    }
+
+    impl Simulation for Counter {
+        type State = u32;
+
+        fn state(&self) -> u32 {
+            self.value
+        }
+
+        fn step(&mut self) {
+            self.value = if self.value >= self.limit {
+                self.restart
+            } else {
+                self.value + 1
+            };
+        }
+    }
+
+    #[test]
+    fn simulation_detects_fixed_point() {
+        let mut counter = Counter {
+            value: 0,
+            limit: 3,
+            restart: 3,
+        };
+
+        assert_eq!(Outcome::FixedPoint { step: 3 }, simulate(&mut counter, 100));
+    }
+
+    #[test]
+    fn simulation_detects_cycle_and_where_it_began() {
+        let mut counter = Counter {
+            value: 0,
+            limit: 5,
+            restart: 2,
+        };
+
+        assert_eq!(
+            Outcome::Cycle {
+                start: 2,
+                period: 4
+            },
+            simulate(&mut counter, 100)
+        );
+    }
+
+    #[test]
+    fn simulation_gives_up_after_max_steps() {
+        let mut counter = Counter {
+            value: 0,
+            limit: 100,
+            restart: 0,
+        };
+
+        assert_eq!(Outcome::Unsettled { steps: 10 }, simulate(&mut counter, 10));
+        assert_eq!(10, counter.value);
+    }
+}
@@ -1,20 +1,32 @@
 use std::error::Error;
 use std::fs;
-use std::io::Error as IoError;
+use std::io::{self, BufReader, Error as IoError};
 use std::path::PathBuf;
 
-use clap::{App, Arg};
+use clap::{App, Arg, SubCommand};
 
 mod days;
 
 fn load_data_from_file(day: u8) -> Result<Vec<String>, IoError> {
     let path: PathBuf = ["input", &format!("day_{:02}", day)].iter().collect();
+    load_lines(path)
+}
+
+fn load_lines(path: PathBuf) -> Result<Vec<String>, IoError> {
     let contents = fs::read_to_string(path)?;
     let result = contents.lines().map(str::to_string).collect();
 
     Ok(result)
 }
 
+/// Reads the file given with `--input`, or the day's input if there isn't one.
+fn load_input(input: Option<PathBuf>, day: u8) -> Result<Vec<String>, IoError> {
+    match input {
+        Some(path) => load_lines(path),
+        None => load_data_from_file(day),
+    }
+}
+
 fn day_is_in_range(value: String) -> Result<(), String> {
     match value.parse::<u8>() {
         Ok(day) => {
@@ -33,31 +45,645 @@ fn day_is_in_range(value: String) -> Result<(), String> {
     Ok(())
 }
 
-fn process_args() -> u8 {
+fn is_a_number(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(String::from("Not a number")),
+    }
+}
+
+/// Checks that a string length is short enough for the day 19 generator to count up to.
+fn is_a_string_length(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(length) if length <= 1000 => Ok(()),
+        Ok(_) => Err(String::from("Length must be at most 1000")),
+        Err(_) => Err(String::from("Not a number")),
+    }
+}
+
+/// Checks that a word width is one the day 14 decoder supports.
+fn is_a_word_width(value: String) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(width) if (1..=64).contains(&width) => Ok(()),
+        Ok(_) => Err(String::from("Width must be between 1 and 64")),
+        Err(_) => Err(String::from("Not a number")),
+    }
+}
+
+enum Command {
+    /// Compiles a day 8 program into its binary form.
+    Assemble {
+        program: Option<PathBuf>,
+        output: PathBuf,
+    },
+    /// Lists a day 8 program, compiled or not, as source code.
+    Disassemble { program: Option<PathBuf> },
+    /// Counts the day 7 bags that can hold a bag of some color and the bags it must hold.
+    Bags {
+        input: Option<PathBuf>,
+        color: String,
+    },
+    /// Lists the memory of the day 14 decoder after running a program.
+    Dump {
+        input: Option<PathBuf>,
+        version: String,
+        width: u32,
+    },
+    /// Writes a static analysis of a day 8 program, as a report or a DOT graph.
+    Analyze { program: Option<PathBuf>, dot: bool },
+    /// Runs the day 8 debugger on a program, reading commands from a script if one is given.
+    Debug {
+        program: Option<PathBuf>,
+        script: Option<PathBuf>,
+    },
+    /// Evaluates expressions with the day 18 calculator, reading them from a script if one is
+    /// given.
+    Calc {
+        mode: String,
+        script: Option<PathBuf>,
+    },
+    /// Lists the day 19 messages that match a rule, after applying any rule overrides.
+    Match {
+        input: Option<PathBuf>,
+        overrides: Option<PathBuf>,
+        start: usize,
+        analyze: bool,
+    },
+    /// Reports the invalid values on the day 16 nearby tickets, optionally saving the decoded
+    /// tickets as CSV or listing the ways the fields can be given to the columns.
+    Tickets {
+        input: Option<PathBuf>,
+        prefix: String,
+        csv: Option<PathBuf>,
+        assignments: Option<usize>,
+    },
+    /// Writes strings that a day 19 rule matches, or counts the ways every rule matches them.
+    Generate {
+        input: Option<PathBuf>,
+        overrides: Option<PathBuf>,
+        start: usize,
+        max_len: usize,
+        generation: days::Generation,
+    },
+}
+
+struct Args {
+    day: u8,
+    frames: Option<PathBuf>,
+    generations: usize,
+    command: Option<Command>,
+}
+
+fn process_args() -> Args {
     let matches = App::new("Advent of Code 2020 Solution Runner")
         .version("0.1.0")
         .author("Jeff Mattfield")
         .about("Runs solutions to the problems posed during the Advent of Code 2020 (https://adventofcode.com/2020)")
         .arg(
             Arg::with_name("day")
-                .help(&*format!("the day of the month (1-{})", days::days_implemented()))
+                .help(&format!("the day of the month (1-{})", days::days_implemented()))
                 .index(1)
                 .validator(day_is_in_range)
                 .required(false)
         )
+        .arg(
+            Arg::with_name("frames")
+                .help("instead of solving, saves a frame of each generation of the day's simulations to this directory")
+                .long("frames")
+                .value_name("DIR")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("generations")
+                .help("the number of generations to save frames for")
+                .long("generations")
+                .value_name("COUNT")
+                .takes_value(true)
+                .default_value("6")
+                .validator(is_a_number)
+        )
+        .subcommand(
+            SubCommand::with_name("debug")
+                .about("Steps through a day 8 program in a debugger")
+                .arg(
+                    Arg::with_name("script")
+                        .help("a file of debugger commands to run instead of reading them interactively")
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("program")
+                        .help("the program to debug, instead of the day 8 input")
+                        .long("program")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("analyze")
+                .about("Reports the basic blocks, unreachable code and cycles of a day 8 program")
+                .arg(
+                    Arg::with_name("program")
+                        .help("the program to analyze, instead of the day 8 input")
+                        .long("program")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("dot")
+                        .help("writes the control-flow graph in Graphviz DOT format instead")
+                        .long("dot")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("assemble")
+                .about("Compiles a day 8 program into its binary form")
+                .arg(
+                    Arg::with_name("output")
+                        .help("the file to write the compiled program to")
+                        .index(1)
+                        .required(true)
+                )
+                .arg(
+                    Arg::with_name("program")
+                        .help("the program to compile, instead of the day 8 input")
+                        .long("program")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("disassemble")
+                .about("Lists a day 8 program as source code with labeled jumps")
+                .arg(
+                    Arg::with_name("program")
+                        .help("the program to list, compiled or not, instead of the day 8 input")
+                        .long("program")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("dump")
+                .about("Lists every non-zero address in the day 14 decoder's memory after running its program")
+                .arg(
+                    Arg::with_name("version")
+                        .help("the version of the decoder chip, which decides how masks apply")
+                        .long("version")
+                        .value_name("VERSION")
+                        .possible_values(&["v1", "v2"])
+                        .default_value("v2")
+                )
+                .arg(
+                    Arg::with_name("width")
+                        .help("the number of bits in a word of memory")
+                        .long("width")
+                        .value_name("BITS")
+                        .default_value("36")
+                        .validator(is_a_word_width)
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("the program to run, instead of the day 14 input")
+                        .long("input")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("bags")
+                .about("Counts the day 7 bags that can hold a bag of some color and the bags it must hold")
+                .arg(
+                    Arg::with_name("color")
+                        .help("the color of the bag to count for")
+                        .index(1)
+                        .default_value("shiny gold")
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("the rules to use, instead of the day 7 input")
+                        .long("input")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("tickets")
+                .about("Reports the invalid values on the day 16 nearby tickets and multiplies your ticket's fields")
+                .arg(
+                    Arg::with_name("prefix")
+                        .help("the start of the names of the fields to multiply")
+                        .long("prefix")
+                        .value_name("PREFIX")
+                        .default_value("departure")
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .help("saves your ticket and the valid nearby tickets to this file as CSV, by field name")
+                        .long("csv")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("assignments")
+                        .help("lists up to this many ways the fields can be given to the columns")
+                        .long("assignments")
+                        .value_name("LIMIT")
+                        .takes_value(true)
+                        .validator(is_a_number)
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("the rules and tickets to use, instead of the day 16 input")
+                        .long("input")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("calc")
+                .about("Evaluates expressions line by line with the day 18 calculator")
+                .arg(
+                    Arg::with_name("script")
+                        .help("a file of expressions and commands to run instead of reading them interactively")
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("mode")
+                        .help("the order operators are applied in")
+                        .long("mode")
+                        .value_name("MODE")
+                        .possible_values(&["left-to-right", "addition-first", "standard"])
+                        .default_value("standard")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("match")
+                .about("Lists the day 19 messages that match a rule")
+                .arg(
+                    Arg::with_name("overrides")
+                        .help("a file of rules that replace or add to the input's rules")
+                        .long("overrides")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("start")
+                        .help("the rule messages must match")
+                        .long("start")
+                        .value_name("RULE")
+                        .default_value("0")
+                        .validator(is_a_number)
+                )
+                .arg(
+                    Arg::with_name("analyze")
+                        .help("reports problems with the rules and where each failing message goes wrong")
+                        .long("analyze")
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("the rules and messages to use, instead of the day 19 input")
+                        .long("input")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("generate")
+                .about("Counts the ways each day 19 rule matches strings, or lists the strings a rule matches")
+                .arg(
+                    Arg::with_name("all")
+                        .help("lists every string the start rule matches")
+                        .long("all")
+                        .conflicts_with("sample")
+                )
+                .arg(
+                    Arg::with_name("sample")
+                        .help("lists this many random strings the start rule matches")
+                        .long("sample")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .validator(is_a_number)
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .help("the seed for picking random strings")
+                        .long("seed")
+                        .value_name("SEED")
+                        .default_value("2020")
+                        .validator(is_a_number)
+                )
+                .arg(
+                    Arg::with_name("max-length")
+                        .help("the length of the longest strings to generate")
+                        .long("max-length")
+                        .value_name("LENGTH")
+                        .default_value("24")
+                        .validator(is_a_string_length)
+                )
+                .arg(
+                    Arg::with_name("overrides")
+                        .help("a file of rules that replace or add to the input's rules")
+                        .long("overrides")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("start")
+                        .help("the rule to generate strings for")
+                        .long("start")
+                        .value_name("RULE")
+                        .default_value("0")
+                        .validator(is_a_number)
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("the rules to use, instead of the day 19 input")
+                        .long("input")
+                        .value_name("FILE")
+                        .takes_value(true)
+                )
+        )
         .get_matches();
 
-    let day: u8 = matches.value_of("day").unwrap_or("20").parse().unwrap();
+    let command = match matches.subcommand() {
+        ("debug", Some(m)) => Some(Command::Debug {
+            program: m.value_of("program").map(PathBuf::from),
+            script: m.value_of("script").map(PathBuf::from),
+        }),
+        ("analyze", Some(m)) => Some(Command::Analyze {
+            program: m.value_of("program").map(PathBuf::from),
+            dot: m.is_present("dot"),
+        }),
+        ("assemble", Some(m)) => Some(Command::Assemble {
+            program: m.value_of("program").map(PathBuf::from),
+            output: PathBuf::from(m.value_of("output").unwrap()),
+        }),
+        ("disassemble", Some(m)) => Some(Command::Disassemble {
+            program: m.value_of("program").map(PathBuf::from),
+        }),
+        ("dump", Some(m)) => Some(Command::Dump {
+            input: m.value_of("input").map(PathBuf::from),
+            version: m.value_of("version").unwrap().to_string(),
+            width: m.value_of("width").unwrap().parse().unwrap(),
+        }),
+        ("bags", Some(m)) => Some(Command::Bags {
+            input: m.value_of("input").map(PathBuf::from),
+            color: m.value_of("color").unwrap().to_string(),
+        }),
+        ("tickets", Some(m)) => Some(Command::Tickets {
+            input: m.value_of("input").map(PathBuf::from),
+            prefix: m.value_of("prefix").unwrap().to_string(),
+            csv: m.value_of("csv").map(PathBuf::from),
+            assignments: m.value_of("assignments").map(|n| n.parse().unwrap()),
+        }),
+        ("calc", Some(m)) => Some(Command::Calc {
+            mode: m.value_of("mode").unwrap().to_string(),
+            script: m.value_of("script").map(PathBuf::from),
+        }),
+        ("match", Some(m)) => Some(Command::Match {
+            input: m.value_of("input").map(PathBuf::from),
+            overrides: m.value_of("overrides").map(PathBuf::from),
+            start: m.value_of("start").unwrap().parse().unwrap(),
+            analyze: m.is_present("analyze"),
+        }),
+        ("generate", Some(m)) => Some(Command::Generate {
+            input: m.value_of("input").map(PathBuf::from),
+            overrides: m.value_of("overrides").map(PathBuf::from),
+            start: m.value_of("start").unwrap().parse().unwrap(),
+            max_len: m.value_of("max-length").unwrap().parse().unwrap(),
+            generation: match m.value_of("sample") {
+                Some(count) => days::Generation::Sample {
+                    count: count.parse().unwrap(),
+                    seed: m.value_of("seed").unwrap().parse().unwrap(),
+                },
+                None if m.is_present("all") => days::Generation::All,
+                None => days::Generation::Counts,
+            },
+        }),
+        _ => None,
+    };
+
+    Args {
+        day: matches.value_of("day").unwrap_or("20").parse().unwrap(),
+        frames: matches.value_of("frames").map(PathBuf::from),
+        generations: matches.value_of("generations").unwrap().parse().unwrap(),
+        command,
+    }
+}
+
+/// Reads a day 8 program from a file, which may hold source code or a compiled program, or the
+/// day's input if no file is given.
+fn load_program(program: Option<PathBuf>) -> Result<Vec<String>, Box<dyn Error>> {
+    let path = match program {
+        Some(path) => path,
+        None => return Ok(load_data_from_file(8)?),
+    };
+
+    let bytes = fs::read(path)?;
+    if days::is_compiled(&bytes) {
+        return Ok(days::decompile(&bytes).map_err(|e| e.to_string())?);
+    }
+
+    Ok(String::from_utf8(bytes)?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+fn assemble(program: Option<PathBuf>, output: PathBuf) -> Result<(), Box<dyn Error>> {
+    let data = load_program(program)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    let bytes = days::compile(&data).map_err(|e| e.to_string())?;
+    fs::write(&output, bytes)?;
+    println!("Saved compiled program to {}", output.display());
+
+    Ok(())
+}
+
+fn disassemble(program: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let data = load_program(program)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    days::list(&data, &mut io::stdout())?;
+
+    Ok(())
+}
+
+fn analyze(program: Option<PathBuf>, dot: bool) -> Result<(), Box<dyn Error>> {
+    let data = load_program(program)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    days::analyze(&data, dot, &mut io::stdout())?;
+
+    Ok(())
+}
+
+fn describe_bag(input: Option<PathBuf>, color: &str) -> Result<(), IoError> {
+    let data = load_input(input, 7)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    days::describe_bag(&data, color, &mut io::stdout())
+}
+
+fn dump_memory(input: Option<PathBuf>, version: &str, width: u32) -> Result<(), IoError> {
+    let data = load_input(input, 14)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    days::dump_memory(&data, version, width, &mut io::stdout())
+}
+
+fn debug(program: Option<PathBuf>, script: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let data = load_program(program)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+    let mut out = io::stdout();
+
+    match script {
+        Some(path) => {
+            let mut input = BufReader::new(fs::File::open(path)?);
+            days::debug(&data, &mut input, &mut out, false)?;
+        }
+        None => days::debug(&data, &mut io::stdin().lock(), &mut out, true)?,
+    }
 
-    day
+    Ok(())
+}
+
+fn validate_tickets(
+    input: Option<PathBuf>,
+    prefix: &str,
+    csv: Option<PathBuf>,
+    assignments: Option<usize>,
+) -> Result<(), IoError> {
+    let data = load_input(input, 16)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+
+    days::validate_tickets(&data, prefix, &mut io::stdout())?;
+    if let Some(path) = csv {
+        days::export_tickets(&data, &mut fs::File::create(&path)?)?;
+        println!("Saved tickets to {}", path.display());
+    }
+    if let Some(limit) = assignments {
+        days::list_assignments(&data, limit, &mut io::stdout())?;
+    }
+
+    Ok(())
+}
+
+fn calc(mode: &str, script: Option<PathBuf>) -> Result<(), IoError> {
+    let mut out = io::stdout();
+
+    match script {
+        Some(path) => {
+            let mut input = BufReader::new(fs::File::open(path)?);
+            days::calculate(mode, &mut input, &mut out, false)
+        }
+        None => days::calculate(mode, &mut io::stdin().lock(), &mut out, true),
+    }
+}
+
+/// Reads day 19 rules and messages from a file, or the day's input if no file is given, along
+/// with any rule overrides.
+fn load_rules(
+    input: Option<PathBuf>,
+    overrides: Option<PathBuf>,
+) -> Result<(Vec<String>, Vec<String>), IoError> {
+    let data = load_input(input, 19)?;
+    let overrides = overrides.map(load_lines).transpose()?.unwrap_or_default();
+
+    Ok((data, overrides))
+}
+
+fn match_messages(
+    input: Option<PathBuf>,
+    overrides: Option<PathBuf>,
+    start: usize,
+    analyze: bool,
+) -> Result<(), IoError> {
+    let (data, overrides) = load_rules(input, overrides)?;
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+    let overrides: Vec<_> = overrides.iter().map(String::as_str).collect();
+
+    days::match_messages(&data, &overrides, start, analyze, &mut io::stdout())
+}
+
+fn generate_strings(
+    input: Option<PathBuf>,
+    overrides: Option<PathBuf>,
+    start: usize,
+    max_len: usize,
+    generation: days::Generation,
+) -> Result<(), IoError> {
+    let (mut data, overrides) = load_rules(input, overrides)?;
+    // A file of just rules needs the blank line that would separate them from messages.
+    if !data.iter().any(String::is_empty) {
+        data.push(String::new());
+    }
+    let data: Vec<_> = data.iter().map(String::as_str).collect();
+    let overrides: Vec<_> = overrides.iter().map(String::as_str).collect();
+
+    days::generate_strings(
+        &data,
+        &overrides,
+        start,
+        max_len,
+        generation,
+        &mut io::stdout(),
+    )
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let day = process_args();
-    let data = load_data_from_file(day)?;
+    let args = process_args();
+    match args.command {
+        Some(Command::Assemble { program, output }) => return assemble(program, output),
+        Some(Command::Disassemble { program }) => return disassemble(program),
+        Some(Command::Analyze { program, dot }) => return analyze(program, dot),
+        Some(Command::Bags { input, color }) => return Ok(describe_bag(input, &color)?),
+        Some(Command::Dump {
+            input,
+            version,
+            width,
+        }) => return Ok(dump_memory(input, &version, width)?),
+        Some(Command::Debug { program, script }) => return debug(program, script),
+        Some(Command::Tickets {
+            input,
+            prefix,
+            csv,
+            assignments,
+        }) => return Ok(validate_tickets(input, &prefix, csv, assignments)?),
+        Some(Command::Calc { mode, script }) => return Ok(calc(&mode, script)?),
+        Some(Command::Match {
+            input,
+            overrides,
+            start,
+            analyze,
+        }) => return Ok(match_messages(input, overrides, start, analyze)?),
+        Some(Command::Generate {
+            input,
+            overrides,
+            start,
+            max_len,
+            generation,
+        }) => {
+            return Ok(generate_strings(
+                input, overrides, start, max_len, generation,
+            )?)
+        }
+        None => {}
+    }
+
+    let data = load_data_from_file(args.day)?;
     let data: Vec<_> = data.iter().map(String::as_str).collect();
 
-    let runner = days::get_runner(day);
+    if let Some(dir) = &args.frames {
+        let dump_frames = days::get_frame_dumper(args.day)
+            .ok_or_else(|| format!("Day {} doesn't run a simulation", args.day))?;
+        dump_frames(&data, dir, args.generations)?;
+        println!("Saved frames to {}", dir.display());
+        return Ok(());
+    }
+
+    let runner = days::get_runner(args.day);
     days::run(runner, &data);
 
     Ok(())